@@ -0,0 +1,547 @@
+//! NamsoGen 命令行工具，无需图形界面即可在 CI 中生成测试数据。
+
+use clap::{Parser, Subcommand};
+use namso_core::bin_database::{BinCriteria, BinDatabase, CardSegment, CardType};
+use namso_core::card_generator::{CardData, CreditCardGenerator, GenerateOptions};
+use namso_core::clock::{Clock, FixedClock, SystemClock};
+use namso_core::cvv::CvkPair;
+use namso_core::expiry::ExpiryPolicy;
+use namso_core::exporters::{export_cards_as, CardExporter};
+use namso_core::fake_data::{generate_fake_users, generate_lorem_ipsum};
+use namso_core::fpe::{FpeKey, FpeMode, PanCipher};
+use namso_core::history::CardHistory;
+use namso_core::networks::CardNetworkRegistry;
+use namso_core::token::{TokenOptions, TokenVault};
+use namso_core::validator::{
+    validate_card_list, validate_card_record, validate_card_with_cvv, validate_cards, validate_emv_data_with, CardRecord,
+    Locale,
+};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "namso-cli", version, about = "信用卡测试号码生成器（命令行版）")]
+struct Cli {
+    /// 输出文件路径，默认输出到标准输出
+    #[arg(short, long, global = true)]
+    output: Option<PathBuf>,
+
+    /// 额外的网络定义文件（TOML / JSON），与内置网络合并
+    #[arg(long, global = true)]
+    networks: Option<PathBuf>,
+
+    /// 额外的 BIN 元数据文件（CSV / JSON），与内置 BIN 表合并
+    #[arg(long, global = true)]
+    bins: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+// 命令只解析一次，不必为了变体大小装箱
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+enum Command {
+    /// 生成信用卡数据
+    Generate {
+        /// 卡网络标识（内置网络或 --networks 中定义的网络），random 表示随机
+        #[arg(short, long, default_value = "random")]
+        network: String,
+        /// 生成数量
+        #[arg(short, long, default_value_t = 10)]
+        quantity: usize,
+        /// BIN 码，可包含 x 占位符
+        #[arg(short, long)]
+        bin: Option<String>,
+        /// 过期策略：future:12-36（12–36 个月后）、exact:2030-01、expired:3（3 个月前已过期）、edge（本月到期）
+        #[arg(long)]
+        expiry: Option<ExpiryPolicy>,
+        /// 过期月份
+        #[arg(long)]
+        exp_month: Option<u32>,
+        /// 过期年份
+        #[arg(long)]
+        exp_year: Option<i32>,
+        /// 包含 CVV
+        #[arg(long)]
+        cvv: bool,
+        /// 覆盖网络的安全码位数（如 3 或 4），0 表示不生成
+        #[arg(long)]
+        cvv_length: Option<usize>,
+        /// 测试 CVK 对（32 位十六进制），设置后 CVV 按 CVV2 算法计算而非随机
+        #[arg(long)]
+        cvk: Option<CvkPair>,
+        /// 生成 ISO 7813 Track 1 / Track 2 磁道数据（含持卡人姓名），用 --format TRACK 导出
+        #[arg(long)]
+        tracks: bool,
+        /// 磁道中的服务码（默认 201）
+        #[arg(long)]
+        service_code: Option<String>,
+        /// 包含余额
+        #[arg(long)]
+        balance: bool,
+        /// 余额货币
+        #[arg(long, default_value = "USD")]
+        currency: String,
+        /// 按发卡国家筛选 BIN（ISO 3166-1 alpha-2，如 FR）
+        #[arg(long)]
+        country: Option<String>,
+        /// 按卡类型筛选 BIN（credit / debit / prepaid）
+        #[arg(long)]
+        card_type: Option<CardType>,
+        /// 按客户类型筛选 BIN（consumer / commercial）
+        #[arg(long)]
+        segment: Option<CardSegment>,
+        /// 按产品等级筛选 BIN（如 classic / gold / platinum）
+        #[arg(long)]
+        tier: Option<String>,
+        /// 随机种子，须与 --today 一起使用，相同种子与日期得到完全相同的结果
+        #[arg(long, requires = "today")]
+        seed: Option<u64>,
+        /// 以指定日期（YYYY-MM-DD）代替今天计算过期日期；指定 --seed 时必填
+        #[arg(long)]
+        today: Option<FixedClock>,
+        /// 保证生成的卡号互不重复
+        #[arg(long)]
+        unique: bool,
+        /// 已生成卡号记录文件，跨多次运行去重（隐含 --unique）
+        #[arg(long)]
+        history: Option<PathBuf>,
+        /// 使用全部 CPU 核心并行生成（固定种子时结果可复现，但与顺序生成不同）
+        #[arg(long)]
+        parallel: bool,
+        /// 导出格式（PIPE / CSV / JSON / XML / SQL / CARD / TRACK / EMV）
+        #[arg(short, long, default_value = "PIPE")]
+        format: String,
+    },
+    /// 验证卡号，任一卡号无效时返回非零退出码
+    Validate {
+        /// 待验证的卡号，未提供时从标准输入逐行读取
+        numbers: Vec<String>,
+        /// 同时按识别出的网络检查安全码（如 Amex CID 为 4 位）
+        #[arg(long)]
+        cvv: Option<String>,
+        /// 输入为十六进制 EMV 数据（BER-TLV），验证其中的卡号
+        #[arg(long)]
+        emv: bool,
+        /// 批量验证整段输入（PIPE / CSV / JSON），输出逐行结果与汇总（JSON）
+        #[arg(long, conflicts_with_all = ["cvv", "emv"])]
+        batch: bool,
+        /// 批量验证的输入文件，未提供时从标准输入读取
+        #[arg(short, long, requires = "batch")]
+        input: Option<PathBuf>,
+        /// 批量输入的格式（PIPE / CSV / JSON），默认自动识别
+        #[arg(long, requires = "batch")]
+        input_format: Option<String>,
+        /// 提示文字的语言（zh / en），批量结果中的错误码不受影响
+        #[arg(long, default_value = "zh")]
+        lang: Locale,
+    },
+    /// 验证整条卡记录（卡号、有效期、安全码、姓名、磁道），输出逐字段结果（JSON），
+    /// 任一字段无效时返回非零退出码
+    ValidateRecord {
+        number: String,
+        /// 有效期（MM/YY、MM/YYYY 或 MMYY）
+        #[arg(long)]
+        expiry: String,
+        /// 安全码，网络没有安全码时省略
+        #[arg(long, default_value = "")]
+        cvv: String,
+        /// 持卡人姓名
+        #[arg(long)]
+        name: Option<String>,
+        /// Track 1 数据，检查其中的卡号、有效期与姓名
+        #[arg(long)]
+        track1: Option<String>,
+        /// Track 2 数据，检查其中的卡号与有效期
+        #[arg(long)]
+        track2: Option<String>,
+        /// 以指定日期（YYYY-MM-DD）代替今天判断是否过期
+        #[arg(long)]
+        today: Option<FixedClock>,
+        /// 提示文字的语言（zh / en）
+        #[arg(long, default_value = "zh")]
+        lang: Locale,
+    },
+    /// 查询卡号或 BIN 的发卡行信息（JSON）
+    Lookup {
+        /// 卡号或 BIN，未提供时从标准输入逐行读取
+        numbers: Vec<String>,
+    },
+    /// 将 JSON 卡数据转换为其他格式
+    Export {
+        /// JSON 卡数据文件，未提供时从标准输入读取
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// 导出格式（PIPE / CSV / JSON / XML / SQL / CARD / TRACK / EMV）
+        #[arg(short, long)]
+        format: String,
+    },
+    /// 保格式加密卡号（保留 BIN、同网络同长度、校验位有效），任一卡号无法处理时返回非零退出码
+    Mask {
+        /// 待处理的卡号，未提供时从标准输入逐行读取
+        numbers: Vec<String>,
+        /// AES 密钥（32 / 48 / 64 位十六进制）
+        #[arg(long)]
+        key: FpeKey,
+        /// 加密模式：ff1 / ff3-1
+        #[arg(long, default_value = "ff1")]
+        mode: FpeMode,
+        /// 调整值（十六进制），FF3-1 须为 14 位，默认为空（FF3-1 为全零）
+        #[arg(long, default_value = "")]
+        tweak: String,
+        /// 用同一密钥还原加密过的卡号
+        #[arg(long)]
+        decrypt: bool,
+    },
+    /// 为 JSON 卡数据分配网络令牌，输出令牌记录（JSON）并写入令牌库
+    Tokenize {
+        /// 令牌库文件（JSON），不存在时新建
+        #[arg(long)]
+        vault: PathBuf,
+        /// JSON 卡数据文件，未提供时从标准输入读取
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// 令牌请求方 ID（11 位数字，默认 40000000001）
+        #[arg(long)]
+        requestor_id: Option<String>,
+        /// 令牌 BIN 或 BIN 模式，默认从卡所属网络的 IIN 区间中选择与资金卡不同的 BIN
+        #[arg(long)]
+        token_bin: Option<String>,
+        /// 令牌有效期策略（写法同 generate --expiry），默认 36 个月后到期
+        #[arg(long)]
+        token_expiry: Option<ExpiryPolicy>,
+        /// 随机种子，须与 --today 一起使用，相同种子与日期得到完全相同的结果
+        #[arg(long, requires = "today")]
+        seed: Option<u64>,
+        /// 以指定日期（YYYY-MM-DD）代替今天计算令牌有效期；指定 --seed 时必填
+        #[arg(long)]
+        today: Option<FixedClock>,
+    },
+    /// 由令牌查找资金卡号，任一令牌不存在时返回非零退出码
+    Detokenize {
+        /// 令牌库文件（JSON）
+        #[arg(long)]
+        vault: PathBuf,
+        /// 令牌，未提供时从标准输入逐行读取
+        tokens: Vec<String>,
+    },
+    /// 生成虚假用户数据（JSON）
+    Users {
+        /// 用户数量
+        #[arg(short, long, default_value_t = 10)]
+        count: usize,
+    },
+    /// 生成 Lorem Ipsum 文本
+    Lorem {
+        /// 段落数量
+        #[arg(short, long, default_value_t = 3)]
+        paragraphs: usize,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<ExitCode, String> {
+    let mut code = ExitCode::SUCCESS;
+    let registry = match &cli.networks {
+        Some(path) => CardNetworkRegistry::builtin_with_file(path)?,
+        None => CardNetworkRegistry::builtin(),
+    };
+    let bin_database = match &cli.bins {
+        Some(path) => BinDatabase::builtin_with_file(path)?,
+        None => BinDatabase::builtin(),
+    };
+
+    let content = match cli.command {
+        Command::Generate {
+            network,
+            quantity,
+            bin,
+            expiry,
+            exp_month,
+            exp_year,
+            cvv,
+            cvv_length,
+            cvk,
+            tracks,
+            service_code,
+            balance,
+            currency,
+            country,
+            card_type,
+            segment,
+            tier,
+            seed,
+            today,
+            unique,
+            history,
+            parallel,
+            format,
+        } => {
+            let mut generator = build_generator(seed, today)
+                .with_registry(registry)
+                .with_bin_database(bin_database);
+            let card_history = match &history {
+                Some(path) => CardHistory::load(path)?,
+                None => CardHistory::new(),
+            };
+            let options = GenerateOptions {
+                network,
+                quantity,
+                expiry,
+                exp_month,
+                exp_year,
+                include_cvv: cvv,
+                cvv_length,
+                cvk,
+                include_balance: balance,
+                currency: Some(currency),
+                bin_code: bin,
+                criteria: BinCriteria {
+                    country,
+                    card_type,
+                    segment,
+                    tier,
+                },
+                unique: unique || history.is_some(),
+                include_tracks: tracks,
+                service_code,
+            };
+            // 边生成边写出，大批量时不占用额外内存
+            let writer: Box<dyn Write> = match &cli.output {
+                Some(path) => {
+                    let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                    Box::new(BufWriter::new(file))
+                }
+                None => Box::new(BufWriter::new(io::stdout().lock())),
+            };
+            let result = write_cards(&mut generator, &options, card_history, writer, &format, parallel);
+            if let (Err(_), Some(path)) = (&result, &cli.output) {
+                // 出错时删除未写完的文件
+                let _ = fs::remove_file(path);
+            }
+            let (card_history, mut writer) = result?;
+            if cli.output.is_none() {
+                writeln!(writer).map_err(|e| e.to_string())?;
+                writer.flush().map_err(|e| e.to_string())?;
+            }
+            if let Some(path) = &history {
+                card_history.save(path)?;
+            }
+            return Ok(code);
+        }
+        Command::Validate { numbers, batch: true, input, input_format, lang, .. } => {
+            let mut batch = if numbers.is_empty() {
+                validate_cards(&read_input(input.as_ref())?, input_format.as_deref(), &registry, &bin_database)?
+            } else {
+                validate_card_list(&numbers, &registry, &bin_database)
+            };
+            batch.localize(lang);
+            if batch.summary.invalid > 0 {
+                code = ExitCode::FAILURE;
+            }
+            serde_json::to_string_pretty(&batch).map_err(|e| e.to_string())?
+        }
+        Command::ValidateRecord { number, expiry, cvv, name, track1, track2, today, lang } => {
+            let record = CardRecord { number, expiry, cvv, cardholder: name, track1, track2 };
+            let today = today.map_or_else(|| SystemClock.today(), |clock| clock.today());
+            let mut result = validate_card_record(&record, today, &registry, &bin_database);
+            result.localize(lang);
+            if !result.valid {
+                code = ExitCode::FAILURE;
+            }
+            serde_json::to_string_pretty(&result).map_err(|e| e.to_string())?
+        }
+        Command::Validate { numbers, cvv, emv, lang, .. } => {
+            let mut lines = Vec::new();
+            for number in &read_numbers(numbers)? {
+                let mut result = if emv {
+                    match validate_emv_data_with(number, &registry, &bin_database) {
+                        Ok(result) => result,
+                        Err(e) => {
+                            // 无法解析的 EMV 数据同样记为无效，继续处理其余输入
+                            code = ExitCode::FAILURE;
+                            lines.push(format!("{}|invalid|-|{}", number, e));
+                            continue;
+                        }
+                    }
+                } else {
+                    validate_card_with_cvv(number, cvv.as_deref(), &registry, &bin_database)
+                };
+                result.localize(lang);
+                if !result.valid {
+                    code = ExitCode::FAILURE;
+                }
+                lines.push(format!(
+                    "{}|{}|{}|{}",
+                    number,
+                    if result.valid { "valid" } else { "invalid" },
+                    result.network.as_deref().unwrap_or("-"),
+                    result.reason
+                ));
+            }
+            lines.join("\n")
+        }
+        Command::Lookup { numbers } => {
+            let mut results = Vec::new();
+            for number in read_numbers(numbers)? {
+                let digits: String = number.chars().filter(|c| c.is_ascii_digit()).collect();
+                let info = bin_database.lookup(&digits).cloned();
+                if info.is_none() {
+                    code = ExitCode::FAILURE;
+                }
+                results.push(serde_json::json!({ "input": number, "bin_info": info }));
+            }
+            serde_json::to_string_pretty(&results).map_err(|e| e.to_string())?
+        }
+        Command::Export { input, format } => {
+            let json = read_input(input.as_ref())?;
+            let cards: Vec<CardData> =
+                serde_json::from_str(&json).map_err(|e| format!("无法解析卡数据: {}", e))?;
+            export_cards_as(&cards, &format)?
+        }
+        Command::Mask { numbers, key, mode, tweak, decrypt } => {
+            let cipher = PanCipher::with_hex_tweak(mode, &key, &tweak)?;
+            let mut lines = Vec::new();
+            for number in &read_numbers(numbers)? {
+                let result = if decrypt {
+                    cipher.decrypt_pan(number, &registry)
+                } else {
+                    cipher.encrypt_pan(number, &registry)
+                };
+                match result {
+                    Ok(masked) => lines.push(format!("{}|{}", number, masked)),
+                    Err(e) => {
+                        code = ExitCode::FAILURE;
+                        lines.push(format!("{}|-|{}", number, e));
+                    }
+                }
+            }
+            lines.join("\n")
+        }
+        Command::Tokenize { vault, input, requestor_id, token_bin, token_expiry, seed, today } => {
+            let json = read_input(input.as_ref())?;
+            let cards: Vec<CardData> =
+                serde_json::from_str(&json).map_err(|e| format!("无法解析卡数据: {}", e))?;
+            let mut generator = build_generator(seed, today).with_registry(registry);
+            let options = TokenOptions {
+                requestor_id,
+                token_bin,
+                expiry: token_expiry,
+            };
+
+            let mut token_vault = TokenVault::load(&vault)?;
+            let records = cards
+                .iter()
+                .map(|card| generator.tokenize(card, &options, &mut token_vault))
+                .collect::<Result<Vec<_>, String>>()?;
+            token_vault.save(&vault)?;
+            serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?
+        }
+        Command::Detokenize { vault, tokens } => {
+            let token_vault = TokenVault::load(&vault)?;
+            let mut lines = Vec::new();
+            for token in &read_numbers(tokens)? {
+                match token_vault.detokenize(token) {
+                    Ok(record) => lines.push(format!(
+                        "{}|{}|{}|{}|{}",
+                        token, record.pan, record.pan_expiry, record.network, record.requestor_id
+                    )),
+                    Err(e) => {
+                        code = ExitCode::FAILURE;
+                        lines.push(format!("{}|-|{}", token, e));
+                    }
+                }
+            }
+            lines.join("\n")
+        }
+        Command::Users { count } => {
+            serde_json::to_string_pretty(&generate_fake_users(count)).map_err(|e| e.to_string())?
+        }
+        Command::Lorem { paragraphs } => generate_lorem_ipsum(paragraphs),
+    };
+
+    write_output(cli.output.as_ref(), &content)?;
+    Ok(code)
+}
+
+/// 创建生成器：`--seed` 须与 `--today` 一起使用（由参数解析保证），同一命令任何时候运行结果都相同
+fn build_generator(seed: Option<u64>, today: Option<FixedClock>) -> CreditCardGenerator {
+    let generator = seed.map_or_else(CreditCardGenerator::new, CreditCardGenerator::with_seed);
+    match today {
+        Some(clock) => generator.with_clock(clock),
+        None => generator,
+    }
+}
+
+/// 边生成边写出，返回更新后的历史记录与写出端
+fn write_cards(
+    generator: &mut CreditCardGenerator,
+    options: &GenerateOptions,
+    mut card_history: CardHistory,
+    writer: Box<dyn Write>,
+    format: &str,
+    parallel: bool,
+) -> Result<(CardHistory, Box<dyn Write>), String> {
+    let mut exporter = CardExporter::new(writer, format)?;
+    if parallel {
+        generator.generate_card_chunks_parallel(options, &mut card_history, |chunk| {
+            chunk.iter().try_for_each(|card| exporter.write_card(card))
+        })?;
+    } else {
+        let mut stream = generator.generate_card_stream_with_history(options, card_history)?;
+        for card in stream.by_ref() {
+            exporter.write_card(&card?)?;
+        }
+        card_history = stream.into_history();
+    }
+    Ok((card_history, exporter.finish()?))
+}
+
+/// 命令行参数为空时从标准输入逐行读取
+fn read_numbers(numbers: Vec<String>) -> Result<Vec<String>, String> {
+    if !numbers.is_empty() {
+        return Ok(numbers);
+    }
+
+    Ok(read_input(None)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+fn read_input(path: Option<&PathBuf>) -> Result<String, String> {
+    match path {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf).map_err(|e| e.to_string())?;
+            Ok(buf)
+        }
+    }
+}
+
+fn write_output(path: Option<&PathBuf>, content: &str) -> Result<(), String> {
+    match path {
+        Some(path) => fs::write(path, content).map_err(|e| format!("{}: {}", path.display(), e)),
+        None => {
+            println!("{}", content);
+            Ok(())
+        }
+    }
+}
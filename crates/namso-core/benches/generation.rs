@@ -0,0 +1,65 @@
+//! 批量生成吞吐量基准：`cargo bench -p namso-core`
+//!
+//! 内存生成使用 100 万张卡；1000 万张卡只测流式导出（顺序与并行），避免整批结果占满内存。
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use namso_core::{export_cards_to_writer, CardExporter, CardHistory, CreditCardGenerator, GenerateOptions};
+use std::io;
+
+const IN_MEMORY: usize = 1_000_000;
+const STREAMED: usize = 10_000_000;
+
+fn options(quantity: usize) -> GenerateOptions {
+    GenerateOptions {
+        quantity,
+        include_cvv: true,
+        ..Default::default()
+    }
+}
+
+fn bench_in_memory(c: &mut Criterion) {
+    let mut group = c.benchmark_group("in_memory");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(IN_MEMORY as u64));
+    let options = options(IN_MEMORY);
+
+    group.bench_with_input(BenchmarkId::new("sequential", IN_MEMORY), &options, |b, options| {
+        b.iter(|| CreditCardGenerator::with_seed(1).generate_card_data(options).unwrap())
+    });
+    group.bench_with_input(BenchmarkId::new("parallel", IN_MEMORY), &options, |b, options| {
+        b.iter(|| CreditCardGenerator::with_seed(1).generate_card_data_parallel(options).unwrap())
+    });
+
+    group.finish();
+}
+
+fn bench_streamed(c: &mut Criterion) {
+    let mut group = c.benchmark_group("streamed_csv");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(STREAMED as u64));
+    let options = options(STREAMED);
+
+    group.bench_with_input(BenchmarkId::new("sequential", STREAMED), &options, |b, options| {
+        b.iter(|| {
+            let mut generator = CreditCardGenerator::with_seed(1);
+            let stream = generator.generate_card_stream(options).unwrap();
+            export_cards_to_writer(stream, "CSV", io::sink()).unwrap()
+        })
+    });
+    group.bench_with_input(BenchmarkId::new("parallel", STREAMED), &options, |b, options| {
+        b.iter(|| {
+            let mut exporter = CardExporter::new(io::sink(), "CSV").unwrap();
+            CreditCardGenerator::with_seed(1)
+                .generate_card_chunks_parallel(options, &mut CardHistory::new(), |chunk| {
+                    chunk.iter().try_for_each(|card| exporter.write_card(card))
+                })
+                .unwrap();
+            exporter.written()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_in_memory, bench_streamed);
+criterion_main!(benches);
@@ -0,0 +1,364 @@
+//! 离线 BIN / IIN 元数据：发卡行、国家、卡类型与产品等级。
+//!
+//! 内置表随库打包（`data/bins.csv`），可从 CSV / JSON 文件加载补充或覆盖条目。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, LazyLock};
+
+/// 内置 BIN 表只解析一次，见 [`BinDatabase::shared_builtin`]
+static SHARED_BUILTIN: LazyLock<Arc<BinDatabase>> = LazyLock::new(|| Arc::new(BinDatabase::builtin()));
+
+const BUILTIN_BINS: &str = include_str!("../data/bins.csv");
+
+/// 卡片资金类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardType {
+    Credit,
+    Debit,
+    Prepaid,
+}
+
+impl fmt::Display for CardType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CardType::Credit => "credit",
+            CardType::Debit => "debit",
+            CardType::Prepaid => "prepaid",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for CardType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "credit" => Ok(CardType::Credit),
+            "debit" => Ok(CardType::Debit),
+            "prepaid" => Ok(CardType::Prepaid),
+            _ => Err(format!("未知的卡类型: {}", s)),
+        }
+    }
+}
+
+/// 客户类型：个人卡或商务卡
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CardSegment {
+    #[default]
+    Consumer,
+    Commercial,
+}
+
+impl fmt::Display for CardSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CardSegment::Consumer => "consumer",
+            CardSegment::Commercial => "commercial",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for CardSegment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "consumer" => Ok(CardSegment::Consumer),
+            "commercial" => Ok(CardSegment::Commercial),
+            _ => Err(format!("未知的客户类型: {}", s)),
+        }
+    }
+}
+
+/// 一条 BIN 元数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BinInfo {
+    /// 6 位或 8 位 IIN
+    pub bin: String,
+    /// 网络标识，如 `visa`
+    pub network: String,
+    /// 发卡行名称
+    pub issuer: String,
+    /// 发卡国家（ISO 3166-1 alpha-2）
+    pub country: String,
+    pub card_type: CardType,
+    /// 产品等级，如 `classic`、`gold`、`platinum`
+    pub tier: String,
+    /// 个人卡或商务卡，未填写时视为个人卡
+    #[serde(default)]
+    pub segment: CardSegment,
+}
+
+impl BinInfo {
+    /// IIN 位数（6 或 8）
+    pub fn iin_length(&self) -> usize {
+        self.bin.len()
+    }
+
+    fn normalize(mut self) -> Result<Self, String> {
+        if !matches!(self.bin.len(), 6 | 8) || !self.bin.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("BIN 必须是 6 位或 8 位数字: {:?}", self.bin));
+        }
+        if self.country.len() != 2 || !self.country.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(format!("BIN {} 的国家代码无效: {:?}", self.bin, self.country));
+        }
+        self.network = self.network.trim().to_lowercase();
+        self.country = self.country.to_uppercase();
+        self.tier = self.tier.trim().to_lowercase();
+        Ok(self)
+    }
+}
+
+/// BIN 筛选条件，未设置的字段不参与筛选
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BinCriteria {
+    /// 发卡国家（ISO 3166-1 alpha-2，不区分大小写）
+    pub country: Option<String>,
+    pub card_type: Option<CardType>,
+    pub segment: Option<CardSegment>,
+    /// 产品等级（不区分大小写）
+    pub tier: Option<String>,
+}
+
+impl BinCriteria {
+    /// 是否没有设置任何条件
+    pub fn is_empty(&self) -> bool {
+        self.country.is_none() && self.card_type.is_none() && self.segment.is_none() && self.tier.is_none()
+    }
+
+    /// 判断 BIN 元数据是否满足全部条件
+    pub fn matches(&self, info: &BinInfo) -> bool {
+        self.country.as_ref().is_none_or(|c| c.eq_ignore_ascii_case(&info.country))
+            && self.card_type.is_none_or(|t| t == info.card_type)
+            && self.segment.is_none_or(|s| s == info.segment)
+            && self.tier.as_ref().is_none_or(|t| t.eq_ignore_ascii_case(&info.tier))
+    }
+}
+
+/// BIN 元数据表，按 IIN 精确匹配，8 位条目优先于 6 位条目
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BinDatabase {
+    entries: Vec<BinInfo>,
+    index: HashMap<String, usize>,
+}
+
+impl BinDatabase {
+    /// 随库打包的内置 BIN 表
+    pub fn builtin() -> Self {
+        Self::from_csv_str(BUILTIN_BINS).expect("内置 BIN 表无效")
+    }
+
+    /// 进程内共享的内置 BIN 表，首次调用时解析，之后直接复用
+    pub fn shared_builtin() -> Arc<Self> {
+        Arc::clone(&SHARED_BUILTIN)
+    }
+
+    /// 空表
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// 从 CSV 解析（表头 `bin,network,issuer,country,card_type,tier[,segment]`，`#` 开头为注释）
+    pub fn from_csv_str(content: &str) -> Result<Self, String> {
+        let mut reader = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+
+        let mut database = Self::empty();
+        for record in reader.deserialize::<BinInfo>() {
+            let info = record.map_err(|e| format!("无法解析 BIN 表: {}", e))?;
+            database.insert(info)?;
+        }
+        Ok(database)
+    }
+
+    /// 从 JSON 数组解析
+    pub fn from_json_str(content: &str) -> Result<Self, String> {
+        let entries: Vec<BinInfo> =
+            serde_json::from_str(content).map_err(|e| format!("无法解析 BIN 表: {}", e))?;
+
+        let mut database = Self::empty();
+        for info in entries {
+            database.insert(info)?;
+        }
+        Ok(database)
+    }
+
+    /// 从文件加载，`.json` 按 JSON 解析，其余按 CSV 解析
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            Self::from_json_str(&content)
+        } else {
+            Self::from_csv_str(&content)
+        }
+    }
+
+    /// 在内置表基础上合并文件中的条目
+    pub fn builtin_with_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let mut database = Self::builtin();
+        database.merge(Self::from_file(path)?);
+        Ok(database)
+    }
+
+    /// 添加条目；相同 BIN 的条目会被替换
+    pub fn insert(&mut self, info: BinInfo) -> Result<(), String> {
+        let info = info.normalize()?;
+
+        match self.index.get(&info.bin) {
+            Some(&i) => self.entries[i] = info,
+            None => {
+                self.index.insert(info.bin.clone(), self.entries.len());
+                self.entries.push(info);
+            }
+        }
+        Ok(())
+    }
+
+    /// 合并另一张表，冲突时以后者为准
+    pub fn merge(&mut self, other: BinDatabase) {
+        for info in other.entries {
+            // 条目已在 other 中校验过
+            let _ = self.insert(info);
+        }
+    }
+
+    /// 查询卡号（或 BIN）对应的元数据，先匹配 8 位 IIN，再匹配 6 位
+    pub fn lookup(&self, card_number: &str) -> Option<&BinInfo> {
+        [8, 6]
+            .into_iter()
+            .filter_map(|len| card_number.get(..len))
+            .find_map(|prefix| self.index.get(prefix))
+            .map(|&i| &self.entries[i])
+    }
+
+    /// 按条件筛选条目，`network` 为网络标识，`None` 表示不限网络
+    pub fn find(&self, criteria: &BinCriteria, network: Option<&str>) -> Vec<&BinInfo> {
+        self.entries
+            .iter()
+            .filter(|info| network.is_none_or(|n| n == info.network))
+            .filter(|info| criteria.matches(info))
+            .collect()
+    }
+
+    /// 全部条目，按加入顺序排列
+    pub fn entries(&self) -> &[BinInfo] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::networks::CardNetworkRegistry;
+
+    #[test]
+    fn test_builtin_entries_match_their_network() {
+        let database = BinDatabase::builtin();
+        let registry = CardNetworkRegistry::builtin();
+        assert!(!database.is_empty());
+
+        for info in database.entries() {
+            let network = registry
+                .get_network_by_identifier(&info.network)
+                .unwrap_or_else(|| panic!("{} 的网络未知: {}", info.bin, info.network));
+            assert!(network.matches_bin(&info.bin), "{} 不属于 {}", info.bin, info.network);
+        }
+    }
+
+    #[test]
+    fn test_lookup_prefers_eight_digit_iin() {
+        let database = BinDatabase::builtin();
+
+        let info = database.lookup("4571736012345678").unwrap();
+        assert_eq!(info.iin_length(), 8);
+        assert_eq!(info.card_type, CardType::Prepaid);
+
+        let info = database.lookup("4571739912345678").unwrap();
+        assert_eq!(info.bin, "457173");
+        assert_eq!(info.country, "FR");
+        assert_eq!(info.card_type, CardType::Debit);
+
+        assert!(database.lookup("4999999999999999").is_none());
+        assert!(database.lookup("4571").is_none());
+    }
+
+    #[test]
+    fn test_find_by_criteria() {
+        let database = BinDatabase::builtin();
+        let criteria = BinCriteria {
+            country: Some("fr".to_string()),
+            card_type: Some(CardType::Debit),
+            ..Default::default()
+        };
+
+        let found = database.find(&criteria, Some("visa"));
+        assert!(!found.is_empty());
+        assert!(found.iter().all(|info| info.country == "FR" && info.card_type == CardType::Debit && info.network == "visa"));
+
+        let commercial = BinCriteria {
+            segment: Some(CardSegment::Commercial),
+            ..Default::default()
+        };
+        assert!(database.find(&commercial, None).iter().all(|info| info.segment == CardSegment::Commercial));
+        assert!(!database.find(&commercial, Some("mastercard")).is_empty());
+
+        let none = BinCriteria {
+            country: Some("ZZ".to_string()),
+            ..Default::default()
+        };
+        assert!(database.find(&none, None).is_empty());
+    }
+
+    #[test]
+    fn test_merge_overrides_entries() {
+        let mut database = BinDatabase::builtin();
+        let before = database.len();
+        let update = BinDatabase::from_json_str(
+            r#"[
+                {"bin": "411111", "network": "visa", "issuer": "Updated Bank", "country": "us", "card_type": "debit", "tier": "Gold"},
+                {"bin": "49999999", "network": "visa", "issuer": "New Bank", "country": "NL", "card_type": "prepaid", "tier": "classic"}
+            ]"#,
+        )
+        .unwrap();
+        database.merge(update);
+
+        assert_eq!(database.len(), before + 1);
+        let info = database.lookup("4111111111111111").unwrap();
+        assert_eq!(info.issuer, "Updated Bank");
+        assert_eq!(info.country, "US");
+        assert_eq!(info.tier, "gold");
+        assert_eq!(database.lookup("4999999912345678").unwrap().country, "NL");
+    }
+
+    #[test]
+    fn test_rejects_invalid_rows() {
+        assert!(BinDatabase::from_csv_str("bin,network,issuer,country,card_type,tier\n4111,visa,X,US,credit,classic").is_err());
+        assert!(BinDatabase::from_csv_str("bin,network,issuer,country,card_type,tier\n411111,visa,X,USA,credit,classic").is_err());
+        assert!(BinDatabase::from_csv_str("bin,network,issuer,country,card_type,tier\n411111,visa,X,US,charge,classic").is_err());
+    }
+}
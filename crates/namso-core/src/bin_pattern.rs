@@ -0,0 +1,525 @@
+//! BIN 模式：描述卡号每一位允许的取值。
+//!
+//! 语法：
+//!
+//! - `0`–`9`：固定数字
+//! - `x` / `X`：任意数字
+//! - `[0-4]`、`[137]`、`[0-25-9]`：数字集合，可包含区间
+//! - `(...)`：分组，常与重复一起使用
+//! - `{n}`：重复前一个元素 n 次，如 `x{6}`、`(12){3}`
+//! - `c` / `C`：校验位所在位置
+//! - 空格与 `-` 仅用于分隔，会被忽略
+//!
+//! 包含 `c` 或以任意数字位（如 `x`）结尾的模式描述完整卡号，未标出 `c` 时最后一位视为校验位；
+//! 其余模式只描述前缀，长度由卡网络决定。
+
+use rand::Rng;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// 卡号最大长度
+const MAX_LENGTH: usize = 19;
+
+/// 分组最大嵌套层数
+const MAX_DEPTH: usize = 8;
+
+/// 一位允许的数字集合（按位存储 0–9）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DigitSet(u16);
+
+impl DigitSet {
+    const ANY: DigitSet = DigitSet(0b11_1111_1111);
+
+    fn single(digit: u32) -> Self {
+        DigitSet(1 << digit)
+    }
+
+    fn range(start: u32, end: u32) -> Self {
+        DigitSet((start..=end).fold(0, |bits, d| bits | (1 << d)))
+    }
+
+    fn contains(self, digit: u32) -> bool {
+        digit < 10 && self.0 & (1 << digit) != 0
+    }
+
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    fn sample<R: Rng + ?Sized>(self, rng: &mut R) -> char {
+        self.nth(rng.gen_range(0..self.len()) as usize)
+    }
+
+    /// 集合中从小到大第 `n` 个数字
+    fn nth(self, n: usize) -> char {
+        let digit = (0..10).filter(|&d| self.contains(d)).nth(n).unwrap_or(0);
+        char::from_digit(digit, 10).unwrap_or('0')
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    Digits(DigitSet),
+    Check,
+}
+
+/// BIN 模式解析错误，`position` 为出错字符的下标（从 0 开始）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinPatternError {
+    Empty,
+    UnexpectedChar { position: usize, found: char },
+    UnclosedClass { position: usize },
+    EmptyClass { position: usize },
+    InvalidRange { position: usize, start: char, end: char },
+    UnclosedGroup { position: usize },
+    EmptyGroup { position: usize },
+    InvalidRepeat { position: usize },
+    MultipleCheckDigits,
+    TooLong { length: usize },
+    TooDeep { position: usize },
+}
+
+impl fmt::Display for BinPatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinPatternError::Empty => write!(f, "BIN 模式为空"),
+            BinPatternError::UnexpectedChar { position, found } => {
+                write!(f, "BIN 模式第 {} 个字符 {:?} 无效", position + 1, found)
+            }
+            BinPatternError::UnclosedClass { position } => {
+                write!(f, "BIN 模式第 {} 个字符处的 [ 没有闭合", position + 1)
+            }
+            BinPatternError::EmptyClass { position } => {
+                write!(f, "BIN 模式第 {} 个字符处的数字集合为空", position + 1)
+            }
+            BinPatternError::InvalidRange { position, start, end } => {
+                write!(f, "BIN 模式第 {} 个字符处的区间 {}-{} 无效", position + 1, start, end)
+            }
+            BinPatternError::UnclosedGroup { position } => {
+                write!(f, "BIN 模式第 {} 个字符处的 ( 没有闭合", position + 1)
+            }
+            BinPatternError::EmptyGroup { position } => {
+                write!(f, "BIN 模式第 {} 个字符处的分组为空", position + 1)
+            }
+            BinPatternError::InvalidRepeat { position } => {
+                write!(f, "BIN 模式第 {} 个字符处的重复次数无效", position + 1)
+            }
+            BinPatternError::MultipleCheckDigits => write!(f, "BIN 模式只能包含一个校验位"),
+            BinPatternError::TooLong { length } => {
+                write!(f, "BIN 模式长度 {} 超过 {} 位", length, MAX_LENGTH)
+            }
+            BinPatternError::TooDeep { position } => {
+                write!(f, "BIN 模式第 {} 个字符处的分组嵌套超过 {} 层", position + 1, MAX_DEPTH)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BinPatternError {}
+
+/// 解析后的 BIN 模式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinPattern {
+    slots: Vec<Slot>,
+    prefix: bool,
+}
+
+impl BinPattern {
+    /// 解析模式字符串
+    pub fn parse(pattern: &str) -> Result<Self, BinPatternError> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            position: 0,
+        };
+        let mut slots = parser.sequence(0)?;
+        if let Some(&found) = parser.chars.get(parser.position) {
+            return Err(BinPatternError::UnexpectedChar { position: parser.position, found });
+        }
+        if slots.is_empty() {
+            return Err(BinPatternError::Empty);
+        }
+
+        let checks = slots.iter().filter(|slot| **slot == Slot::Check).count();
+        if checks > 1 {
+            return Err(BinPatternError::MultipleCheckDigits);
+        }
+
+        // 未显式标出校验位时，以任意数字位结尾的模式把最后一位作为校验位
+        let ends_with_wildcard = slots.last() == Some(&Slot::Digits(DigitSet::ANY));
+        let prefix = checks == 0 && !ends_with_wildcard;
+        if checks == 0 && ends_with_wildcard {
+            *slots.last_mut().expect("模式非空") = Slot::Check;
+        }
+
+        Ok(Self { slots, prefix })
+    }
+
+    /// 模式描述的位数（仅前缀时为前缀长度）
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// 是否只描述前缀，卡号长度由网络决定
+    pub fn is_prefix(&self) -> bool {
+        self.prefix
+    }
+
+    /// 校验位下标
+    pub fn check_digit_position(&self) -> Option<usize> {
+        self.slots.iter().position(|slot| *slot == Slot::Check)
+    }
+
+    /// 模式中非校验位可能的组合数
+    pub fn combinations(&self) -> u128 {
+        self.combinations_except(0..0)
+    }
+
+    /// 不计 `skip` 范围内的位（如多位校验位覆盖的位置）时，非校验位可能的组合数
+    pub fn combinations_except(&self, skip: Range<usize>) -> u128 {
+        self.slots
+            .iter()
+            .enumerate()
+            .map(|(i, slot)| match slot {
+                Slot::Digits(set) if !skip.contains(&i) => set.len() as u128,
+                _ => 1,
+            })
+            .fold(1, u128::saturating_mul)
+    }
+
+    /// 第 `index` 个组合（`0..combinations()`，按数字从小到大排列），校验位处填 `0`
+    pub fn nth(&self, mut index: u128) -> String {
+        let mut digits: Vec<char> = self.slots
+            .iter()
+            .rev()
+            .map(|slot| match slot {
+                Slot::Digits(set) => {
+                    let n = set.len() as u128;
+                    let digit = set.nth((index % n) as usize);
+                    index /= n;
+                    digit
+                }
+                Slot::Check => '0',
+            })
+            .collect();
+        digits.reverse();
+        digits.into_iter().collect()
+    }
+
+    /// 卡号是否符合模式（校验位只要求是数字）
+    pub fn matches(&self, number: &str) -> bool {
+        let length_ok = if self.prefix {
+            number.len() >= self.slots.len()
+        } else {
+            number.len() == self.slots.len()
+        };
+
+        length_ok
+            && number.chars().all(|c| c.is_ascii_digit())
+            && self.slots.iter().zip(number.chars()).all(|(slot, c)| match slot {
+                Slot::Digits(set) => c.to_digit(10).is_some_and(|d| set.contains(d)),
+                Slot::Check => true,
+            })
+    }
+
+    /// 前 `width` 位的所有可能取值是否都落在给定区间（`width` 位数字的闭区间）的并集内；
+    /// 校验位与模式之外的位视为任意数字
+    pub fn prefix_within(&self, width: usize, ranges: &[(u64, u64)]) -> bool {
+        let sets: Vec<DigitSet> = (0..width)
+            .map(|i| match self.slots.get(i) {
+                Some(Slot::Digits(set)) => *set,
+                _ => DigitSet::ANY,
+            })
+            .collect();
+        let total = sets.iter().map(|set| set.len() as u128).fold(1, u128::saturating_mul);
+
+        // 合并重叠区间后逐段计数，计数之和等于全部组合数即完全落在区间内
+        let mut ranges = ranges.to_vec();
+        ranges.sort_unstable();
+        let mut merged: Vec<(u64, u64)> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        let inside: u128 = merged
+            .iter()
+            .map(|&(start, end)| {
+                let below = start.checked_sub(1).map_or(0, |bound| count_at_most(&sets, bound));
+                count_at_most(&sets, end) - below
+            })
+            .sum();
+        inside == total
+    }
+
+    /// 按模式随机生成各位数字，校验位处填 `0`，由调用方计算
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.slots
+            .iter()
+            .map(|slot| match slot {
+                Slot::Digits(set) => set.sample(rng),
+                Slot::Check => '0',
+            })
+            .collect()
+    }
+}
+
+impl FromStr for BinPattern {
+    type Err = BinPatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// 各位分别取自 `sets`、且不大于 `bound` 的 `sets.len()` 位数字串个数
+fn count_at_most(sets: &[DigitSet], bound: u64) -> u128 {
+    let width = sets.len();
+    let digits: Vec<u32> = format!("{:0width$}", bound, width = width)
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect();
+    if digits.len() > width {
+        // 上界超过 width 位，全部组合都不大于它
+        return sets.iter().map(|set| set.len() as u128).product();
+    }
+
+    let mut count = 0;
+    for (i, (&set, &digit)) in sets.iter().zip(&digits).enumerate() {
+        let rest: u128 = sets[i + 1..].iter().map(|set| set.len() as u128).product();
+        count += (0..digit).filter(|&d| set.contains(d)).count() as u128 * rest;
+        if !set.contains(digit) {
+            return count;
+        }
+    }
+    count + 1
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    /// 解析元素序列，直到输入结束或遇到 `)`
+    fn sequence(&mut self, depth: usize) -> Result<Vec<Slot>, BinPatternError> {
+        let mut slots = Vec::new();
+
+        while let Some(c) = self.peek() {
+            let start = self.position;
+            let atom = match c {
+                ' ' | '\t' | '-' => {
+                    self.position += 1;
+                    continue;
+                }
+                ')' if depth > 0 => break,
+                '0'..='9' => {
+                    self.position += 1;
+                    vec![Slot::Digits(DigitSet::single(c.to_digit(10).unwrap_or(0)))]
+                }
+                'x' | 'X' => {
+                    self.position += 1;
+                    vec![Slot::Digits(DigitSet::ANY)]
+                }
+                'c' | 'C' => {
+                    self.position += 1;
+                    vec![Slot::Check]
+                }
+                '[' => vec![Slot::Digits(self.class()?)],
+                '(' => {
+                    if depth >= MAX_DEPTH {
+                        return Err(BinPatternError::TooDeep { position: start });
+                    }
+                    self.position += 1;
+                    let group = self.sequence(depth + 1)?;
+                    if self.peek() != Some(')') {
+                        return Err(BinPatternError::UnclosedGroup { position: start });
+                    }
+                    if group.is_empty() {
+                        return Err(BinPatternError::EmptyGroup { position: start });
+                    }
+                    self.position += 1;
+                    group
+                }
+                found => return Err(BinPatternError::UnexpectedChar { position: start, found }),
+            };
+
+            let count = self.repeat()?;
+            let length = atom
+                .len()
+                .checked_mul(count)
+                .and_then(|n| n.checked_add(slots.len()))
+                .unwrap_or(usize::MAX);
+            if length > MAX_LENGTH {
+                return Err(BinPatternError::TooLong { length });
+            }
+            for _ in 0..count {
+                slots.extend_from_slice(&atom);
+            }
+        }
+
+        Ok(slots)
+    }
+
+    /// 解析 `[...]` 数字集合
+    fn class(&mut self) -> Result<DigitSet, BinPatternError> {
+        let start = self.position;
+        if !self.chars[start..].contains(&']') {
+            return Err(BinPatternError::UnclosedClass { position: start });
+        }
+        self.position += 1;
+        let mut set = DigitSet(0);
+
+        // 前面已确认存在 `]`
+        while let Some(c) = self.peek() {
+            self.position += 1;
+            match c {
+                ']' => break,
+                '0'..='9' => {
+                    let low = c.to_digit(10).unwrap_or(0);
+                    if self.peek() == Some('-') && self.chars.get(self.position + 1).is_some_and(|n| *n != ']') {
+                        let end = self.chars[self.position + 1];
+                        let high = end.to_digit(10).filter(|&high| high >= low).ok_or(
+                            BinPatternError::InvalidRange { position: self.position - 1, start: c, end },
+                        )?;
+                        self.position += 2;
+                        set.0 |= DigitSet::range(low, high).0;
+                    } else {
+                        set.0 |= DigitSet::single(low).0;
+                    }
+                }
+                found => {
+                    return Err(BinPatternError::UnexpectedChar { position: self.position - 1, found });
+                }
+            }
+        }
+
+        if set.len() == 0 {
+            return Err(BinPatternError::EmptyClass { position: start });
+        }
+        Ok(set)
+    }
+
+    /// 解析可选的 `{n}`，没有时返回 1；次数超过卡号最大长度时无论元素多长都无效
+    fn repeat(&mut self) -> Result<usize, BinPatternError> {
+        if self.peek() != Some('{') {
+            return Ok(1);
+        }
+
+        let start = self.position;
+        let close = self.chars[start..]
+            .iter()
+            .position(|&c| c == '}')
+            .ok_or(BinPatternError::InvalidRepeat { position: start })?;
+        let digits: String = self.chars[start + 1..start + close].iter().collect();
+        let count = digits
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n > 0 && n <= MAX_LENGTH)
+            .ok_or(BinPatternError::InvalidRepeat { position: start })?;
+
+        self.position = start + close + 1;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_parse_full_and_prefix_patterns() {
+        let pattern = BinPattern::parse("4532x1xx").unwrap();
+        assert!(!pattern.is_prefix());
+        assert_eq!(pattern.len(), 8);
+        assert_eq!(pattern.check_digit_position(), Some(7));
+        assert_eq!(pattern.combinations(), 100);
+
+        let pattern = BinPattern::parse("453212").unwrap();
+        assert!(pattern.is_prefix());
+        assert_eq!(pattern.check_digit_position(), None);
+        assert_eq!(pattern.combinations(), 1);
+
+        let pattern = BinPattern::parse("4c").unwrap();
+        assert_eq!(pattern.check_digit_position(), Some(1));
+    }
+
+    #[test]
+    fn test_classes_groups_and_repeats() {
+        let pattern = BinPattern::parse("4[0-25-7](12){2}x{3} c").unwrap();
+        assert_eq!(pattern.len(), 10);
+        assert_eq!(pattern.check_digit_position(), Some(9));
+        assert_eq!(pattern.combinations(), 6 * 1000);
+
+        assert!(pattern.matches("4512120000"));
+        assert!(!pattern.matches("4312120000"));
+        assert!(!pattern.matches("451212000"));
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..50 {
+            assert!(pattern.matches(&pattern.sample(&mut rng)));
+        }
+
+        // 按编号枚举时每个组合恰好出现一次
+        let all: std::collections::HashSet<String> = (0..pattern.combinations()).map(|i| pattern.nth(i)).collect();
+        assert_eq!(all.len(), 6000);
+        assert!(all.iter().all(|number| pattern.matches(number)));
+        assert_eq!(pattern.nth(0), "4012120000");
+
+        assert_eq!(BinPattern::parse("[137]").unwrap().combinations(), 3);
+        assert_eq!(BinPattern::parse("4532-xxxx").unwrap().len(), 8);
+        assert_eq!(BinPattern::parse("4532x{12}").unwrap().check_digit_position(), Some(15));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use BinPatternError::*;
+
+        assert_eq!(BinPattern::parse(""), Err(Empty));
+        assert_eq!(BinPattern::parse("45a"), Err(UnexpectedChar { position: 2, found: 'a' }));
+        assert_eq!(BinPattern::parse("4[0-4"), Err(UnclosedClass { position: 1 }));
+        assert_eq!(BinPattern::parse("4[]"), Err(EmptyClass { position: 1 }));
+        assert_eq!(BinPattern::parse("4[5-2]"), Err(InvalidRange { position: 2, start: '5', end: '2' }));
+        assert_eq!(BinPattern::parse("4(12"), Err(UnclosedGroup { position: 1 }));
+        assert_eq!(BinPattern::parse("4)"), Err(UnexpectedChar { position: 1, found: ')' }));
+        assert_eq!(BinPattern::parse("4x{0}"), Err(InvalidRepeat { position: 2 }));
+        assert_eq!(BinPattern::parse("4x{3"), Err(InvalidRepeat { position: 2 }));
+        assert_eq!(BinPattern::parse("4cxc"), Err(MultipleCheckDigits));
+        assert_eq!(BinPattern::parse("4x{19}"), Err(TooLong { length: 20 }));
+        assert!(BinPattern::parse("(x){99999999999999999999}").is_err());
+        // 空分组与过大的重复次数在展开前就被拒绝，不会长时间循环
+        assert_eq!(BinPattern::parse("4(){999999999999}"), Err(EmptyGroup { position: 1 }));
+        assert_eq!(BinPattern::parse("4( - ){3}"), Err(EmptyGroup { position: 1 }));
+        assert_eq!(BinPattern::parse("4(()){2}"), Err(EmptyGroup { position: 2 }));
+        assert_eq!(BinPattern::parse("4x{999999999999}"), Err(InvalidRepeat { position: 2 }));
+        assert_eq!(BinPattern::parse("4(x){20}"), Err(InvalidRepeat { position: 4 }));
+        assert_eq!(BinPattern::parse(&"(".repeat(10_000)), Err(TooDeep { position: 8 }));
+        assert!(BinPattern::parse("((((((((4))))))))").is_ok());
+    }
+
+    #[test]
+    fn test_prefix_within() {
+        // Mastercard：51-55 与 2221-2720，按 4 位对齐
+        let mastercard = [(5100, 5599), (2221, 2720)];
+        assert!(BinPattern::parse("5[1-5]").unwrap().prefix_within(4, &mastercard));
+        assert!(BinPattern::parse("2[3-6]xxxxxxxxxxxxx").unwrap().prefix_within(4, &mastercard));
+        assert!(!BinPattern::parse("5").unwrap().prefix_within(4, &mastercard));
+        assert!(!BinPattern::parse("2[2-7]").unwrap().prefix_within(4, &mastercard));
+        assert!(!BinPattern::parse("x{16}").unwrap().prefix_within(1, &[(4, 4)]));
+        assert!(BinPattern::parse("4c").unwrap().prefix_within(1, &[(4, 4)]));
+        // 相邻区间合并后整体覆盖
+        assert!(BinPattern::parse("[34]").unwrap().prefix_within(1, &[(3, 3), (4, 4)]));
+    }
+}
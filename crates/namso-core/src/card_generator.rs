@@ -0,0 +1,2044 @@
+//! 卡号、过期日期、CVV 与余额生成。
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use chrono::Datelike;
+use crate::bin_database::{BinCriteria, BinDatabase, BinInfo};
+use crate::bin_pattern::BinPattern;
+use crate::check_digit::CheckDigitAlgorithm;
+use crate::clock::{Clock, FixedClock, SystemClock};
+use crate::cvv::{compute_cvv, compute_cvv_with_length, CvkPair, CvvKind};
+use crate::fake_data::generate_cardholder_name;
+use crate::expiry::{check_year, ExpiryPolicy};
+use crate::history::CardHistory;
+use crate::networks::{CardNetwork, CardNetworkRegistry, CheckDigitScheme, IinRange};
+use crate::token::{TokenOptions, TokenRecord, TokenVault, DEFAULT_TOKEN_EXPIRY};
+use crate::track::{TrackData, DEFAULT_SERVICE_CODE};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 去重时允许的最大连续重复次数，超过后视为可用卡号耗尽
+const MAX_CONSECUTIVE_COLLISIONS: usize = 1000;
+
+/// 并行生成时每个分块的卡数；分块边界固定，结果与线程数无关
+const PARALLEL_CHUNK_SIZE: usize = 8192;
+
+/// 一张生成的卡片数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardData {
+    pub number: String,
+    pub network: String,
+    pub exp_month: String,
+    pub exp_year: String,
+    pub expiry: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cvv: Option<String>,
+    /// 安全码在该网络中的名称（CVV2、CVC2、CID、CVN2 等）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cvv_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    pub bin: String,
+    /// BIN 元数据（发卡行、国家、卡类型），BIN 表中没有记录时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bin_info: Option<BinInfo>,
+    /// 磁道格式的持卡人姓名（`姓/名`），仅在生成磁道数据时设置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cardholder: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_code: Option<String>,
+    /// ISO 7813 Track 1（格式 B）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track1: Option<String>,
+    /// ISO 7813 Track 2
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track2: Option<String>,
+}
+
+/// 批量生成参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerateOptions {
+    /// 网络标识（如 `visa`），`random` 表示每张卡随机选择网络
+    pub network: String,
+    /// 生成数量
+    pub quantity: usize,
+    /// 过期日期策略，设置后忽略 `exp_month` 与 `exp_year`
+    pub expiry: Option<ExpiryPolicy>,
+    /// 指定过期月份
+    pub exp_month: Option<u32>,
+    /// 指定过期年份
+    pub exp_year: Option<i32>,
+    /// 是否生成 CVV
+    pub include_cvv: bool,
+    /// 覆盖网络的安全码位数（3 或 4），0 表示不生成安全码
+    pub cvv_length: Option<usize>,
+    /// 测试 CVK 对，设置后安全码按 CVV2 算法由卡号与有效期计算，可用同一密钥校验
+    pub cvk: Option<CvkPair>,
+    /// 是否生成余额
+    pub include_balance: bool,
+    /// 余额货币，仅在生成余额时使用
+    pub currency: Option<String>,
+    /// BIN 码或 BIN 模式（见 [`crate::bin_pattern`]）
+    pub bin_code: Option<String>,
+    /// 按国家、卡类型、客户类型、产品等级从 BIN 表中选择前缀，不能与 `bin_code` 同时使用
+    pub criteria: BinCriteria,
+    /// 保证卡号在本批次内（以及传入的历史记录中）不重复
+    pub unique: bool,
+    /// 是否生成 Track 1 / Track 2 磁道数据
+    pub include_tracks: bool,
+    /// 磁道中的服务码，默认为 [`DEFAULT_SERVICE_CODE`]
+    pub service_code: Option<String>,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            network: "random".to_string(),
+            quantity: 1,
+            expiry: None,
+            exp_month: None,
+            exp_year: None,
+            include_cvv: false,
+            cvv_length: None,
+            cvk: None,
+            include_balance: false,
+            currency: None,
+            bin_code: None,
+            criteria: BinCriteria::default(),
+            unique: false,
+            include_tracks: false,
+            service_code: None,
+        }
+    }
+}
+
+/// 信用卡号码生成器，持有自己的随机数源
+///
+/// 网络注册表与 BIN 表通过 `Arc` 共享，可以低成本地为每个请求或线程创建生成器。
+pub struct CreditCardGenerator {
+    rng: StdRng,
+    registry: Arc<CardNetworkRegistry>,
+    bin_database: Arc<BinDatabase>,
+    clock: Arc<dyn Clock>,
+}
+
+impl CreditCardGenerator {
+    pub fn new() -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+            registry: CardNetworkRegistry::shared_builtin(),
+            bin_database: BinDatabase::shared_builtin(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// 使用固定种子创建生成器，相同种子与相同参数产生相同卡号；过期日期仍以系统日期为基准，
+    /// 需要任何时候运行都得到相同结果时使用 [`reproducible`](Self::reproducible)
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            registry: CardNetworkRegistry::shared_builtin(),
+            bin_database: BinDatabase::shared_builtin(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// 使用固定种子与固定日期创建生成器，`today` 未指定时使用 [`FixedClock::seeded_default`]，
+    /// 相同种子、日期与参数在任何时候运行都产生完全相同的结果
+    pub fn reproducible(seed: u64, today: Option<FixedClock>) -> Self {
+        Self::with_seed(seed).with_clock(today.unwrap_or_else(FixedClock::seeded_default))
+    }
+
+    /// 使用指定的网络注册表替换内置网络，可传入 `Arc` 与其他生成器共享
+    pub fn with_registry(mut self, registry: impl Into<Arc<CardNetworkRegistry>>) -> Self {
+        self.registry = registry.into();
+        self
+    }
+
+    /// 当前使用的网络注册表
+    pub fn registry(&self) -> &CardNetworkRegistry {
+        &self.registry
+    }
+
+    /// 可修改的网络注册表，用于运行时注册新网络（与其他生成器共享时先复制一份）
+    pub fn registry_mut(&mut self) -> &mut CardNetworkRegistry {
+        Arc::make_mut(&mut self.registry)
+    }
+
+    /// 共享的网络注册表
+    pub fn shared_registry(&self) -> Arc<CardNetworkRegistry> {
+        Arc::clone(&self.registry)
+    }
+
+    /// 使用指定的 BIN 元数据表替换内置表，可传入 `Arc` 与其他生成器共享
+    pub fn with_bin_database(mut self, bin_database: impl Into<Arc<BinDatabase>>) -> Self {
+        self.bin_database = bin_database.into();
+        self
+    }
+
+    /// 当前使用的 BIN 元数据表
+    pub fn bin_database(&self) -> &BinDatabase {
+        &self.bin_database
+    }
+
+    /// 可修改的 BIN 元数据表，用于运行时更新（与其他生成器共享时先复制一份）
+    pub fn bin_database_mut(&mut self) -> &mut BinDatabase {
+        Arc::make_mut(&mut self.bin_database)
+    }
+
+    /// 共享的 BIN 元数据表
+    pub fn shared_bin_database(&self) -> Arc<BinDatabase> {
+        Arc::clone(&self.bin_database)
+    }
+
+    /// 使用指定的时钟计算过期日期（默认为系统时钟），固定时钟可使结果不随运行日期变化
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// 当前使用的时钟
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// 以指定种子创建共享同一注册表、BIN 表与时钟的新生成器
+    pub fn fork(&self, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            registry: Arc::clone(&self.registry),
+            bin_database: Arc::clone(&self.bin_database),
+            clock: Arc::clone(&self.clock),
+        }
+    }
+
+    /// 以系统随机数源创建共享同一注册表、BIN 表与时钟的新生成器
+    pub fn fork_from_entropy(&self) -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+            registry: Arc::clone(&self.registry),
+            bin_database: Arc::clone(&self.bin_database),
+            clock: Arc::clone(&self.clock),
+        }
+    }
+    
+    /// Luhn 算法校验和计算
+    pub fn luhn_checksum(card_num: &str) -> u32 {
+        let digits: Vec<u32> = card_num.chars()
+            .filter_map(|c| c.to_digit(10))
+            .collect();
+        
+        let odd_sum: u32 = digits.iter().rev().step_by(2).sum();
+        let even_sum: u32 = digits.iter().rev().skip(1).step_by(2)
+            .map(|&d| {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            })
+            .sum();
+        
+        (odd_sum + even_sum) % 10
+    }
+    
+    /// 验证卡号是否符合 Luhn 算法
+    pub fn is_luhn_valid(card_num: &str) -> bool {
+        Self::luhn_checksum(card_num) == 0
+    }
+    
+    /// 生成卡号
+    pub fn generate_card_number(
+        &mut self,
+        network: &str,
+        bin_code: Option<&str>
+    ) -> Result<String, String> {
+        let network_info = self.registry.get_network_by_identifier(network)
+            .ok_or_else(|| format!("不支持的网络类型: {}", network))?
+            .clone();
+        
+        // 处理 BIN 模式
+        let (chosen_bin, target_length) = if let Some(bin) = bin_code {
+            let pattern = BinPattern::parse(bin).map_err(|e| e.to_string())?;
+            check_pattern(&pattern, &network_info)?;
+            if !pattern.is_prefix() {
+                return Ok(self.generate_card_number_from_pattern(&pattern, &network_info.check_digit));
+            }
+            // 只描述前缀的模式，使用网络默认长度
+            let length = *network_info.length.first().unwrap();
+            (pattern.sample(&mut self.rng), length)
+        } else {
+            // 随机选择 IIN 区间、区间内的前缀和长度
+            let range_index = self.rng.gen_range(0..network_info.ranges.len());
+            let prefix = network_info.ranges[range_index].sample(&mut self.rng);
+            let length_index = self.rng.gen_range(0..network_info.length.len());
+            (prefix, network_info.length[length_index])
+        };
+        
+        self.complete_card_number(chosen_bin, target_length, &network_info.check_digit)
+    }
+    
+    /// 以已知 BIN 生成卡号，长度从网络允许的长度中随机选择
+    pub fn generate_card_number_from_bin(&mut self, network: &str, bin: &str) -> Result<String, String> {
+        let network_info = self.registry.get_network_by_identifier(network)
+            .ok_or_else(|| format!("不支持的网络类型: {}", network))?;
+        let check_digit = network_info.check_digit.clone();
+        let lengths: Vec<usize> = network_info.length.iter()
+            .copied()
+            .filter(|&length| length > bin.len())
+            .collect();
+        if lengths.is_empty() {
+            return Err(format!("BIN {} 对 {} 过长", bin, network));
+        }
+        
+        let target_length = lengths[self.rng.gen_range(0..lengths.len())];
+        self.complete_card_number(bin.to_string(), target_length, &check_digit)
+    }
+    
+    /// 用随机数字补齐到目标长度并追加校验位（无校验位的网络全部为随机数字）
+    fn complete_card_number(&mut self, prefix: String, target_length: usize, scheme: &CheckDigitScheme) -> Result<String, String> {
+        let algorithm = scheme.algorithm();
+        let payload_length = target_length.saturating_sub(algorithm.check_length());
+        
+        // 生成卡号（不包含校验位）
+        let mut card_number = prefix;
+        while card_number.len() < payload_length {
+            card_number.push_str(&self.rng.gen_range(0..10).to_string());
+        }
+        
+        // 如果超过目标长度，截断
+        if card_number.len() > payload_length {
+            card_number.truncate(payload_length);
+        }
+        
+        // 计算并添加校验位
+        let check_digits = algorithm.compute(&card_number)?;
+        card_number.push_str(&check_digits);
+        
+        Ok(card_number)
+    }
+    
+    /// 按完整长度的 BIN 模式生成卡号，并在模式指定的位置填入校验位
+    fn generate_card_number_from_pattern(&mut self, pattern: &BinPattern, scheme: &CheckDigitScheme) -> String {
+        let mut digits: Vec<u32> = pattern.sample(&mut self.rng)
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .collect();
+        
+        let algorithm = scheme.algorithm();
+        match pattern.check_digit_position() {
+            Some(position) if algorithm.check_length() == 0 => {
+                digits[position] = self.rng.gen_range(0..10);
+            }
+            Some(position) => fill_check_digits(&mut digits, position, algorithm),
+            None => {}
+        }
+        
+        digits_to_string(&digits)
+    }
+    
+    /// 生成过期日期
+    pub fn generate_expiry(&mut self, month: Option<u32>, year: Option<i32>) -> (String, String) {
+        let current_date = self.clock.today();
+        let rng = &mut self.rng;
+        let current_year = current_date.year();
+        let current_month = current_date.month();
+
+        let (exp_month, exp_year) = if let Some(m) = month {
+            if let Some(y) = year {
+                // 如果都指定了，验证是否是未来日期
+                if y < current_year || (y == current_year && m < current_month) {
+                    // 如果不是未来日期，使用默认逻辑
+                    let default_year = rng.gen_range(current_year..=current_year + 8);
+                    if default_year == current_year {
+                        (rng.gen_range(current_month..=12), default_year)
+                    } else {
+                        (rng.gen_range(1..=12), default_year)
+                    }
+                } else {
+                    (m, y)
+                }
+            } else {
+                // 只指定月份，生成年份
+                let default_year = rng.gen_range(current_year..=current_year + 8);
+                if default_year == current_year && m < current_month {
+                    (rng.gen_range(current_month..=12), default_year)
+                } else {
+                    (m, default_year)
+                }
+            }
+        } else if let Some(y) = year {
+            // 只指定年份，生成月份
+            if y == current_year {
+                // 如果是当前年份，月份必须大于等于当前月份
+                (rng.gen_range(current_month..=12), y)
+            } else {
+                (rng.gen_range(1..=12), y)
+            }
+        } else {
+            // 都没指定，完全随机生成未来日期
+            let random_year = rng.gen_range(current_year..=current_year + 8);
+            let random_month = if random_year == current_year {
+                rng.gen_range(current_month..=12)
+            } else {
+                rng.gen_range(1..=12)
+            };
+            (random_month, random_year)
+        };
+
+        (format!("{:02}", exp_month), exp_year.to_string())
+    }
+    
+    /// 按过期策略生成过期日期，允许生成已过期或本月到期的日期
+    pub fn generate_expiry_with(&mut self, policy: &ExpiryPolicy) -> Result<(String, String), String> {
+        let current_date = self.clock.today();
+        let (exp_month, exp_year) = policy.resolve(current_date.year(), current_date.month(), &mut self.rng)?;
+        Ok((format!("{:02}", exp_month), exp_year.to_string()))
+    }
+    
+    /// 生成 CVV
+    pub fn generate_cvv(&mut self, network: &str) -> Result<String, String> {
+        let cvv_length = self.registry.get_network_by_identifier(network)
+            .ok_or_else(|| format!("不支持的网络类型: {}", network))?
+            .cvv_length;
+        
+        Ok(self.random_digits(cvv_length))
+    }
+    
+    fn random_digits(&mut self, length: usize) -> String {
+        (0..length)
+            .map(|_| char::from_digit(self.rng.gen_range(0..10), 10).unwrap())
+            .collect()
+    }
+    
+    /// 生成余额
+    pub fn generate_balance(&mut self, min: f64, max: f64) -> f64 {
+        let balance = self.rng.gen_range(min..=max);
+        (balance * 100.0).round() / 100.0
+    }
+    
+    /// 生成完整卡数据
+    pub fn generate_card_data(&mut self, options: &GenerateOptions) -> Result<Vec<CardData>, String> {
+        self.generate_card_stream(options)?.collect()
+    }
+    
+    /// 生成完整卡数据；`unique` 为真时跳过历史记录中已有的卡号，并把新卡号写入历史记录
+    pub fn generate_card_data_with_history(
+        &mut self,
+        options: &GenerateOptions,
+        history: &mut CardHistory,
+    ) -> Result<Vec<CardData>, String> {
+        // 先检查参数，出错时历史记录保持不变
+        let plan = self.prepare(options, history)?;
+        let mut stream = self.card_stream(options, plan, std::mem::take(history));
+        let cards = stream.by_ref().collect();
+        *history = stream.into_history();
+        cards
+    }
+    
+    /// 逐张生成卡数据，不在内存中保留整批结果
+    pub fn generate_card_stream(&mut self, options: &GenerateOptions) -> Result<CardStream<'_>, String> {
+        self.generate_card_stream_with_history(options, CardHistory::new())
+    }
+    
+    /// 逐张生成卡数据，去重时使用并更新传入的历史记录（生成结束后通过 [`CardStream::into_history`] 取回）
+    pub fn generate_card_stream_with_history(
+        &mut self,
+        options: &GenerateOptions,
+        history: CardHistory,
+    ) -> Result<CardStream<'_>, String> {
+        let plan = self.prepare(options, &history)?;
+        Ok(self.card_stream(options, plan, history))
+    }
+    
+    fn card_stream(&mut self, options: &GenerateOptions, plan: GenerationPlan, history: CardHistory) -> CardStream<'_> {
+        CardStream {
+            generator: self,
+            options: options.clone(),
+            plan,
+            history,
+            generated: 0,
+            failed: false,
+        }
+    }
+    
+    /// 并行生成完整卡数据
+    ///
+    /// 按固定大小分块，每块使用由生成器种子派生的独立随机数源，
+    /// 因此固定种子时结果可复现，且与线程数无关（但与逐张生成的结果不同）。
+    pub fn generate_card_data_parallel(&mut self, options: &GenerateOptions) -> Result<Vec<CardData>, String> {
+        self.generate_card_data_parallel_with_history(options, &mut CardHistory::new())
+    }
+    
+    /// 并行生成完整卡数据；`unique` 为真时按顺序替换重复卡号
+    pub fn generate_card_data_parallel_with_history(
+        &mut self,
+        options: &GenerateOptions,
+        history: &mut CardHistory,
+    ) -> Result<Vec<CardData>, String> {
+        let mut cards = Vec::with_capacity(options.quantity);
+        self.generate_card_chunks_parallel(options, history, |chunk| {
+            cards.extend(chunk);
+            Ok(())
+        })?;
+        Ok(cards)
+    }
+    
+    /// 并行生成，并按顺序把每个分块交给 `sink`，内存中最多保留一批分块，适合大批量直接写入文件
+    ///
+    /// 结果与 [`generate_card_data_parallel`](Self::generate_card_data_parallel) 相同。
+    pub fn generate_card_chunks_parallel<F>(
+        &mut self,
+        options: &GenerateOptions,
+        history: &mut CardHistory,
+        mut sink: F,
+    ) -> Result<(), String>
+    where
+        F: FnMut(Vec<CardData>) -> Result<(), String>,
+    {
+        let mut plan = self.prepare(options, history)?;
+        let seed: u64 = self.rng.gen();
+        let chunk_options = GenerateOptions { unique: false, ..options.clone() };
+        let chunk_count = options.quantity.div_ceil(PARALLEL_CHUNK_SIZE);
+        // 每批分块数只影响内存占用，不影响结果
+        let batch_size = rayon::current_num_threads() * 4;
+        
+        // 去重按分块顺序进行，替换用的卡号来自单独的随机数源，保证结果可复现
+        let mut replacer = self.fork(chunk_seed(seed, u64::MAX));
+        let mut generated = 0;
+        
+        for batch_start in (0..chunk_count).step_by(batch_size) {
+            let this = &*self;
+            let chunks: Vec<Result<Vec<CardData>, String>> = (batch_start..chunk_count.min(batch_start + batch_size))
+                .into_par_iter()
+                .map(|index| {
+                    let start = index * PARALLEL_CHUNK_SIZE;
+                    let quantity = PARALLEL_CHUNK_SIZE.min(options.quantity - start);
+                    this.fork(chunk_seed(seed, index as u64))
+                        .generate_card_data(&GenerateOptions { quantity, ..chunk_options.clone() })
+                })
+                .collect();
+            
+            for chunk in chunks {
+                let mut chunk = chunk?;
+                if options.unique {
+                    for card in chunk.iter_mut() {
+                        if !history.insert(card.number.clone()) {
+                            *card = replacer.next_card(options, &mut plan, history, generated)?;
+                        }
+                        generated += 1;
+                    }
+                } else {
+                    generated += chunk.len();
+                }
+                sink(chunk)?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// 检查参数并准备生成计划
+    fn prepare(&self, options: &GenerateOptions, history: &CardHistory) -> Result<GenerationPlan, String> {
+        // 处理随机网络（按注册顺序选择，保证同一种子下结果稳定）
+        let mut networks = self.get_networks();
+        if networks.is_empty() {
+            return Err("没有可用的卡网络".to_string());
+        }
+        if let Some(bin) = options.bin_code.as_deref() {
+            let pattern = BinPattern::parse(bin).map_err(|e| e.to_string())?;
+            if options.network == "random" {
+                // 随机网络只在与 BIN 模式相符的网络中选择
+                networks.retain(|identifier| self.registry.get_network_by_identifier(identifier)
+                    .is_some_and(|network| check_pattern(&pattern, network).is_ok()));
+                if networks.is_empty() {
+                    return Err(format!("没有与 BIN 模式 {} 相符的卡网络", bin));
+                }
+            } else {
+                let network = self.registry.get_network_by_identifier(&options.network)
+                    .ok_or_else(|| format!("不支持的网络类型: {}", options.network))?;
+                check_pattern(&pattern, network)?;
+            }
+        }
+        if options.cvv_length.is_some_and(|length| length > 4) {
+            return Err("CVV 长度不能超过 4".to_string());
+        }
+        if let Some(month) = options.exp_month.filter(|month| !(1..=12).contains(month)) {
+            return Err(format!("过期月份无效: {}", month));
+        }
+        if let Some(year) = options.exp_year {
+            check_year(year)?;
+        }
+        
+        // 按条件从 BIN 表中挑选前缀
+        let matching_bins = self.matching_bins(&options.network, options.bin_code.as_deref(), &options.criteria)?;
+        
+        // 去重时先确认扣除历史记录后可用卡号数量足够；只扣除当前条件下可能生成的历史卡号，
+        // 多个网络、BIN 共用一个历史文件时互不影响
+        let mut sampler = None;
+        if options.unique {
+            let used = self.history_in_keyspace(options, matching_bins.as_deref(), history)?;
+            let remaining = self.keyspace(options)?.saturating_sub(used);
+            if options.quantity as u128 > remaining {
+                return Err(format!(
+                    "请求生成 {} 张不重复的卡，但当前条件下扣除历史记录后最多只有 {} 个可用卡号",
+                    options.quantity, remaining
+                ));
+            }
+            // 请求数量占剩余可用卡号一半以上时随机重试容易连续重复，改为不重复抽样
+            if options.quantity as u128 * 2 > remaining {
+                sampler = Some(UniqueSampler::new(&self.registry, options, &networks, matching_bins.as_deref())?);
+            }
+        }
+        
+        Ok(GenerationPlan { networks, matching_bins, sampler })
+    }
+    
+    /// 生成下一张卡，`generated` 为此前已生成的数量（用于错误信息）
+    fn next_card(
+        &mut self,
+        options: &GenerateOptions,
+        plan: &mut GenerationPlan,
+        history: &mut CardHistory,
+        generated: usize,
+    ) -> Result<CardData, String> {
+        let (chosen_network, card_number) = match plan.sampler.as_mut() {
+            Some(sampler) => sampler.next(&mut self.rng, &self.bin_database, history)?
+                .ok_or_else(|| format!("已生成 {} 张卡后可用卡号已全部用完", generated))?,
+            None => self.next_card_number(options, plan, history, generated)?,
+        };
+        let chosen_network = chosen_network.as_str();
+        
+        let (month, year) = match &options.expiry {
+            Some(policy) => self.generate_expiry_with(policy)?,
+            None => self.generate_expiry(options.exp_month, options.exp_year),
+        };
+        
+        // 安全码位数为 0（网络没有安全码或请求中关闭）时不生成
+        let cvv = if options.include_cvv {
+            let length = match options.cvv_length {
+                Some(length) => length,
+                None => self.registry.get_network_by_identifier(chosen_network)
+                    .ok_or_else(|| format!("不支持的网络类型: {}", chosen_network))?
+                    .cvv_length,
+            };
+            Some(self.security_code(options, &card_number, &month, &year, length)?)
+        } else {
+            None
+        }
+        .filter(|cvv| !cvv.is_empty());
+        
+        let balance = if options.include_balance {
+            Some(self.generate_balance(100.0, 10000.0))
+        } else {
+            None
+        };
+        
+        let tracks = if options.include_tracks {
+            Some(self.generate_tracks(options, &card_number, &month, &year)?)
+        } else {
+            None
+        };
+        
+        let network = self.registry.get_network_by_identifier(chosen_network);
+        let network_name = network
+            .map(|n| n.name.clone())
+            .unwrap_or_else(|| "Unknown".to_string());
+        let cvv_label = cvv.as_ref().and(network).map(|n| n.cvv_label().to_string());
+        
+        let year_short: String = year.chars().skip(2).collect();
+        let expiry = format!("{}/{}", month, year_short);
+        let bin = card_number.chars().take(6).collect::<String>();
+        let bin_info = self.bin_database.lookup(&card_number).cloned();
+        
+        Ok(CardData {
+            number: card_number,
+            network: network_name,
+            exp_month: month,
+            exp_year: year,
+            expiry,
+            cvv,
+            cvv_label,
+            balance,
+            currency: if options.include_balance { options.currency.clone() } else { None },
+            bin,
+            bin_info,
+            cardholder: tracks.as_ref().map(|t| t.cardholder.clone()),
+            service_code: tracks.as_ref().map(|t| t.service_code.clone()),
+            track1: tracks.as_ref().map(|t| t.track1.clone()),
+            track2: tracks.map(|t| t.track2),
+        })
+    }
+    
+    /// 生成磁道数据，自定义数据为 PVKI（1 位）+ PVV（4 位）+ 磁条 CVV（3 位）；
+    /// 设置了 CVK 时磁条 CVV 按卡上服务码计算，否则随机
+    fn generate_tracks(
+        &mut self,
+        options: &GenerateOptions,
+        card_number: &str,
+        month: &str,
+        year: &str,
+    ) -> Result<TrackData, String> {
+        let service_code = options.service_code.as_deref().unwrap_or(DEFAULT_SERVICE_CODE);
+        let expiry = format!("{}{}", &year[year.len().saturating_sub(2)..], month);
+        let name = generate_cardholder_name(&mut self.rng);
+        let pvv = self.random_digits(4);
+        let cvv = match &options.cvk {
+            Some(keys) => compute_cvv(card_number, &expiry, CvvKind::Cvv.service_code(Some(service_code))?, keys)?,
+            None => self.random_digits(3),
+        };
+        TrackData::new(card_number, &name, &expiry, service_code, &format!("1{}{}", pvv, cvv))
+    }
+    
+    /// 生成指定位数的安全码：设置了 CVK 时按 CVV2 算法计算，否则随机
+    fn security_code(
+        &mut self,
+        options: &GenerateOptions,
+        card_number: &str,
+        month: &str,
+        year: &str,
+        length: usize,
+    ) -> Result<String, String> {
+        match &options.cvk {
+            Some(_) if length == 0 => Ok(String::new()),
+            Some(keys) => {
+                let expiry = format!("{}{}", &year[year.len().saturating_sub(2)..], month);
+                compute_cvv_with_length(card_number, &expiry, CvvKind::Cvv2.service_code(None)?, keys, length)
+            }
+            None => Ok(self.random_digits(length)),
+        }
+    }
+    
+    /// 为卡分配网络令牌并记入令牌库，同一资金卡号与请求方已有令牌时直接返回
+    ///
+    /// 令牌卡号与资金卡号同属一个网络、长度相同，BIN（前 6 位）不同，校验位按网络规则计算。
+    pub fn tokenize(
+        &mut self,
+        card: &CardData,
+        options: &TokenOptions,
+        vault: &mut TokenVault,
+    ) -> Result<TokenRecord, String> {
+        let requestor_id = options.requestor_id()?;
+        if let Some(record) = vault.find(&card.number, requestor_id) {
+            return Ok(record.clone());
+        }
+
+        let network = self.registry.detect(&card.number)
+            .ok_or_else(|| format!("无法识别卡号 {} 的网络", card.number))?
+            .clone();
+        let pattern = options.token_bin.as_deref()
+            .map(|bin| BinPattern::parse(bin).map_err(|e| e.to_string()))
+            .transpose()?;
+        if let Some(pattern) = &pattern {
+            check_pattern(pattern, &network)?;
+            if !pattern.is_prefix() && pattern.len() != card.number.len() {
+                return Err(format!("令牌 BIN 模式长度 {} 与卡号长度 {} 不一致", pattern.len(), card.number.len()));
+            }
+        }
+        let funding_bin = &card.number[..card.number.len().min(6)];
+
+        let mut token = None;
+        for _ in 0..MAX_CONSECUTIVE_COLLISIONS {
+            let candidate = match &pattern {
+                Some(pattern) if !pattern.is_prefix() => {
+                    self.generate_card_number_from_pattern(pattern, &network.check_digit)
+                }
+                Some(pattern) => {
+                    let prefix = pattern.sample(&mut self.rng);
+                    self.complete_card_number(prefix, card.number.len(), &network.check_digit)?
+                }
+                None => {
+                    let range_index = self.rng.gen_range(0..network.ranges.len());
+                    let prefix = network.ranges[range_index].sample(&mut self.rng);
+                    self.complete_card_number(prefix, card.number.len(), &network.check_digit)?
+                }
+            };
+            if !candidate.starts_with(funding_bin) && !vault.contains(&candidate) {
+                token = Some(candidate);
+                break;
+            }
+        }
+        let token = token.ok_or_else(|| format!("无法为卡号 {} 分配 BIN 不同且未使用的令牌卡号", card.number))?;
+
+        let (token_exp_month, token_exp_year) = self.generate_expiry_with(options.expiry.as_ref().unwrap_or(&DEFAULT_TOKEN_EXPIRY))?;
+        let record = TokenRecord {
+            token_expiry: format!("{}/{}", token_exp_month, &token_exp_year[token_exp_year.len().saturating_sub(2)..]),
+            token,
+            token_exp_month,
+            token_exp_year,
+            requestor_id: requestor_id.to_string(),
+            pan: card.number.clone(),
+            pan_expiry: card.expiry.clone(),
+            network: network.name.clone(),
+            created: self.clock.today().format("%Y-%m-%d").to_string(),
+        };
+        vault.insert(record.clone());
+        Ok(record)
+    }
+    
+    /// 随机生成下一个卡号，去重时遇到重复重新生成，返回 `(网络标识, 卡号)`
+    fn next_card_number(
+        &mut self,
+        options: &GenerateOptions,
+        plan: &GenerationPlan,
+        history: &mut CardHistory,
+        generated: usize,
+    ) -> Result<(String, String), String> {
+        let mut collisions = 0;
+        loop {
+            let (chosen_network, number) = if let Some(bins) = plan.matching_bins.as_deref() {
+                let info = &bins[self.rng.gen_range(0..bins.len())];
+                (info.network.clone(), self.generate_card_number_for_bin_info(info)?)
+            } else {
+                let chosen_network = if options.network == "random" {
+                    plan.networks[self.rng.gen_range(0..plan.networks.len())].clone()
+                } else {
+                    options.network.clone()
+                };
+                let number = self.generate_card_number(&chosen_network, options.bin_code.as_deref())?;
+                (chosen_network, number)
+            };
+            
+            if !options.unique || history.insert(number.clone()) {
+                return Ok((chosen_network, number));
+            }
+            collisions += 1;
+            if collisions >= MAX_CONSECUTIVE_COLLISIONS {
+                return Err(format!(
+                    "已生成 {} 张卡后连续 {} 次重复，可用卡号已基本耗尽",
+                    generated, collisions
+                ));
+            }
+        }
+    }
+    
+    /// 当前参数下可生成的不同卡号数量（重叠区间会重复计算，结果为上限）
+    pub fn keyspace(&self, options: &GenerateOptions) -> Result<u128, String> {
+        let networks: Vec<&CardNetwork> = if options.network == "random" {
+            self.registry.networks().iter().collect()
+        } else {
+            vec![self.registry.get_network_by_identifier(&options.network)
+                .ok_or_else(|| format!("不支持的网络类型: {}", options.network))?]
+        };
+        
+        // 前缀之后、校验位之前的随机数字位数对应的组合数，与 UniqueSampler 的分段计数一致
+        let combinations = |length: usize, prefix_len: usize, network: &CardNetwork| -> u128 {
+            let check_length = network.check_digit.algorithm().check_length();
+            10u128.saturating_pow(length.saturating_sub(prefix_len + check_length) as u32)
+        };
+        
+        if let Some(bins) = self.matching_bins(&options.network, options.bin_code.as_deref(), &options.criteria)? {
+            return Ok(bins.iter()
+                .filter_map(|info| self.registry.get_network_by_identifier(&info.network).map(|n| (info, n)))
+                .flat_map(|(info, network)| network.length.iter()
+                    .filter(move |&&length| length > info.bin.len())
+                    .map(move |&length| combinations(length, info.bin.len(), network)))
+                .fold(0, u128::saturating_add));
+        }
+        
+        if let Some(bin) = options.bin_code.as_deref() {
+            // 与 generate_card_number 的处理方式保持一致
+            let pattern = BinPattern::parse(bin).map_err(|e| e.to_string())?;
+            let space = |network: &CardNetwork| {
+                let check_length = network.check_digit.algorithm().check_length();
+                match pattern.check_digit_position() {
+                    _ if pattern.is_prefix() => {
+                        let length = network.length.first().copied().unwrap_or(0);
+                        combinations(length, pattern.len(), network).saturating_mul(pattern.combinations())
+                    }
+                    // 无校验位的网络中，模式的校验位处也是随机数字
+                    Some(_) if check_length == 0 => pattern.combinations().saturating_mul(10),
+                    // 多位校验位从标出的位置起覆盖后面的位（与 fill_check_digits 一致）
+                    Some(position) => {
+                        let start = position.min(pattern.len().saturating_sub(check_length));
+                        pattern.combinations_except(start..start + check_length)
+                    }
+                    None => pattern.combinations(),
+                }
+            };
+            return Ok(networks.into_iter()
+                .filter(|network| check_pattern(&pattern, network).is_ok())
+                .map(space)
+                .max()
+                .unwrap_or(0));
+        }
+        
+        Ok(networks.into_iter()
+            .flat_map(|network| network.ranges.iter().flat_map(move |range| {
+                network.length.iter().map(move |&length| {
+                    combinations(length, range.prefix_len, network).saturating_mul(range.size() as u128)
+                })
+            }))
+            .fold(0, u128::saturating_add))
+    }
+    
+    /// 历史记录中当前参数下可能生成的卡号数量，与 [`Self::keyspace`] 统计的范围一致
+    fn history_in_keyspace(
+        &self,
+        options: &GenerateOptions,
+        matching_bins: Option<&[BinInfo]>,
+        history: &CardHistory,
+    ) -> Result<u128, String> {
+        if history.is_empty() {
+            return Ok(0);
+        }
+        let pattern = options.bin_code.as_deref()
+            .map(|bin| BinPattern::parse(bin).map_err(|e| e.to_string()))
+            .transpose()?;
+        let networks: Vec<&CardNetwork> = if options.network == "random" {
+            self.registry.networks().iter().collect()
+        } else {
+            vec![self.registry.get_network_by_identifier(&options.network)
+                .ok_or_else(|| format!("不支持的网络类型: {}", options.network))?]
+        };
+        let networks: Vec<&CardNetwork> = networks.into_iter()
+            .filter(|network| pattern.as_ref().is_none_or(|pattern| check_pattern(pattern, network).is_ok()))
+            .collect();
+        
+        let possible = |network: &CardNetwork, number: &str| -> bool {
+            if !network.check_digit.algorithm().is_valid(number) {
+                return false;
+            }
+            match (matching_bins, &pattern) {
+                (Some(bins), _) => network.is_valid_length(number.len()) && bins.iter().any(|info| {
+                    info.network == network.identifier
+                        && number.starts_with(&info.bin)
+                        && self.bin_database.lookup(number).is_some_and(|found| found.bin == info.bin)
+                }),
+                (None, Some(pattern)) if pattern.is_prefix() => {
+                    network.length.first() == Some(&number.len()) && pattern.matches(number)
+                }
+                (None, Some(pattern)) => pattern.matches(number),
+                (None, None) => network.matches(number),
+            }
+        };
+        Ok(history.iter()
+            .filter(|number| networks.iter().any(|network| possible(network, number)))
+            .count() as u128)
+    }
+    
+    /// 以 BIN 条目生成卡号，并避开同前缀下属性不同的更具体（8 位）条目
+    fn generate_card_number_for_bin_info(&mut self, info: &BinInfo) -> Result<String, String> {
+        for _ in 0..100 {
+            let number = self.generate_card_number_from_bin(&info.network, &info.bin)?;
+            if self.bin_database.lookup(&number).is_some_and(|found| found.bin == info.bin) {
+                return Ok(number);
+            }
+        }
+        Err(format!("无法在 BIN {} 下生成不属于其他 IIN 的卡号", info.bin))
+    }
+    
+    /// 满足筛选条件的 BIN 条目；没有条件时返回 `None`
+    fn matching_bins(
+        &self,
+        network: &str,
+        bin_code: Option<&str>,
+        criteria: &BinCriteria,
+    ) -> Result<Option<Vec<BinInfo>>, String> {
+        if criteria.is_empty() {
+            return Ok(None);
+        }
+        if bin_code.is_some() {
+            return Err("BIN 码与 BIN 筛选条件不能同时使用".to_string());
+        }
+        
+        let network_filter = (network != "random").then_some(network);
+        let bins: Vec<BinInfo> = self.bin_database
+            .find(criteria, network_filter)
+            .into_iter()
+            .filter(|info| self.registry.get_network_by_identifier(&info.network).is_some())
+            .cloned()
+            .collect();
+        
+        if bins.is_empty() {
+            return Err("BIN 表中没有符合条件的 BIN".to_string());
+        }
+        Ok(Some(bins))
+    }
+    
+    /// 获取所有支持的网络名称
+    pub fn get_networks(&self) -> Vec<String> {
+        self.registry
+            .networks()
+            .iter()
+            .map(|n| n.identifier.clone())
+            .collect()
+    }
+}
+
+/// 校验位不一定在末尾，逐个尝试使整体校验通过的数字；多位校验位（Mod 97-10）
+/// 从校验位位置起连续占用，位于末尾时向前占用
+fn fill_check_digits(digits: &mut [u32], position: usize, algorithm: &dyn CheckDigitAlgorithm) {
+    let check_length = algorithm.check_length();
+    let start = position.min(digits.len().saturating_sub(check_length));
+    let end = (start + check_length).min(digits.len());
+    let fill = |value: u32, digits: &mut [u32]| {
+        let mut value = value;
+        for digit in digits[start..end].iter_mut().rev() {
+            *digit = value % 10;
+            value /= 10;
+        }
+    };
+    let found = (0..10u32.pow(check_length as u32)).find(|&value| {
+        fill(value, digits);
+        algorithm.is_valid(&digits_to_string(digits))
+    });
+    fill(found.unwrap_or(0), digits);
+}
+
+fn digits_to_string(digits: &[u32]) -> String {
+    digits.iter().map(|d| d.to_string()).collect()
+}
+
+/// 检查 BIN 模式是否适用于网络：完整长度的模式长度须是网络允许的长度，只描述前缀的模式
+/// 须能放进网络默认长度；模式所有可能的前缀都须落在网络的 IIN 区间内
+fn check_pattern(pattern: &BinPattern, network: &CardNetwork) -> Result<(), String> {
+    let check_length = network.check_digit.algorithm().check_length();
+    if pattern.is_prefix() {
+        let length = network.length.first().copied().unwrap_or(0);
+        if pattern.len() + check_length > length {
+            return Err(format!("BIN 模式长度 {} 超出 {} 的卡号长度 {}", pattern.len(), network.name, length));
+        }
+    } else if !network.length.contains(&pattern.len()) {
+        return Err(format!(
+            "BIN 模式长度 {} 不是 {} 允许的卡号长度 {:?}",
+            pattern.len(), network.name, network.length
+        ));
+    }
+
+    // 各区间按最长前缀对齐后比较
+    let width = network.ranges.iter().map(|range| range.prefix_len).max().unwrap_or(0);
+    let ranges: Vec<(u64, u64)> = network.ranges.iter()
+        .map(|range| {
+            let scale = 10u64.pow((width - range.prefix_len) as u32);
+            (range.start * scale, (range.end + 1) * scale - 1)
+        })
+        .collect();
+    if !pattern.prefix_within(width, &ranges) {
+        let ranges: Vec<String> = network.ranges.iter().map(|range| range.to_string()).collect();
+        return Err(format!("BIN 模式前缀不在 {} 的 IIN 区间 {} 内", network.name, ranges.join(", ")));
+    }
+    Ok(())
+}
+
+impl Default for CreditCardGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 由基础种子与分块序号派生分块种子（SplitMix64）
+fn chunk_seed(seed: u64, index: u64) -> u64 {
+    let mut z = seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// 生成计划：可选网络、符合条件的 BIN，以及可用卡号所剩不多时使用的不重复抽样器
+struct GenerationPlan {
+    networks: Vec<String>,
+    matching_bins: Option<Vec<BinInfo>>,
+    sampler: Option<UniqueSampler>,
+}
+
+/// 不重复抽样：给当前条件下的全部卡号编号，用稀疏 Fisher–Yates 洗牌逐个取出编号，
+/// 每个编号只取一次，因此在可用卡号用完之前不会因连续重复而失败
+struct UniqueSampler {
+    segments: Vec<Segment>,
+    /// 尚未取出的编号数
+    remaining: u128,
+    /// 洗牌时被换到其他位置的编号
+    swaps: HashMap<u128, u128>,
+}
+
+/// 一段编号连续的卡号：前缀后接 `free_digits` 位数字与校验位
+struct Segment {
+    network: String,
+    source: SegmentSource,
+    scheme: CheckDigitScheme,
+    free_digits: usize,
+    count: u128,
+}
+
+enum SegmentSource {
+    Range(IinRange),
+    /// 只描述前缀的模式，或完整长度的模式（`free_digits` 为校验位处的随机数字位数）
+    Pattern(BinPattern),
+    /// BIN 表条目，生成的卡号须仍归属该条目
+    Bin(String),
+}
+
+impl UniqueSampler {
+    /// 按与随机生成相同的规则划分编号段
+    fn new(
+        registry: &CardNetworkRegistry,
+        options: &GenerateOptions,
+        networks: &[String],
+        matching_bins: Option<&[BinInfo]>,
+    ) -> Result<Self, String> {
+        let network = |identifier: &str| registry.get_network_by_identifier(identifier)
+            .ok_or_else(|| format!("不支持的网络类型: {}", identifier));
+        let chosen: Vec<&str> = if options.network == "random" {
+            networks.iter().map(String::as_str).collect()
+        } else {
+            vec![options.network.as_str()]
+        };
+        
+        let mut segments = Vec::new();
+        let mut push = |network: &CardNetwork, source: SegmentSource, prefixes: u128, free_digits: usize| {
+            segments.push(Segment {
+                network: network.identifier.clone(),
+                source,
+                scheme: network.check_digit.clone(),
+                free_digits,
+                count: prefixes.saturating_mul(10u128.saturating_pow(free_digits as u32)),
+            });
+        };
+        
+        if let Some(bins) = matching_bins {
+            for info in bins {
+                let network = network(&info.network)?;
+                let check_length = network.check_digit.algorithm().check_length();
+                for &length in network.length.iter().filter(|&&length| length > info.bin.len()) {
+                    let free_digits = length.saturating_sub(info.bin.len() + check_length);
+                    push(network, SegmentSource::Bin(info.bin.clone()), 1, free_digits);
+                }
+            }
+        } else if let Some(bin) = options.bin_code.as_deref() {
+            let pattern = BinPattern::parse(bin).map_err(|e| e.to_string())?;
+            for identifier in chosen {
+                let network = network(identifier)?;
+                let check_length = network.check_digit.algorithm().check_length();
+                let free_digits = if pattern.is_prefix() {
+                    network.length.first().copied().unwrap_or(0).saturating_sub(pattern.len() + check_length)
+                } else {
+                    usize::from(check_length == 0 && pattern.check_digit_position().is_some())
+                };
+                push(network, SegmentSource::Pattern(pattern.clone()), pattern.combinations(), free_digits);
+            }
+        } else {
+            for identifier in chosen {
+                let network = network(identifier)?;
+                let check_length = network.check_digit.algorithm().check_length();
+                for range in &network.ranges {
+                    for &length in &network.length {
+                        let free_digits = length.saturating_sub(range.prefix_len + check_length);
+                        push(network, SegmentSource::Range(*range), range.size() as u128, free_digits);
+                    }
+                }
+            }
+        }
+        
+        let remaining = segments.iter().map(|segment| segment.count).fold(0, u128::saturating_add);
+        Ok(Self { segments, remaining, swaps: HashMap::new() })
+    }
+    
+    /// 取出下一个不在历史记录中的卡号并记入历史记录，返回 `(网络标识, 卡号)`；编号用完时返回 `None`
+    fn next(
+        &mut self,
+        rng: &mut StdRng,
+        bin_database: &BinDatabase,
+        history: &mut CardHistory,
+    ) -> Result<Option<(String, String)>, String> {
+        while self.remaining > 0 {
+            let picked = rng.gen_range(0..self.remaining);
+            self.remaining -= 1;
+            let last = self.remaining;
+            let moved = self.swaps.remove(&last).unwrap_or(last);
+            let mut index = if picked == last {
+                moved
+            } else {
+                self.swaps.insert(picked, moved).unwrap_or(picked)
+            };
+            
+            let Some(segment) = self.segments.iter().find(|segment| {
+                let found = index < segment.count;
+                if !found {
+                    index -= segment.count;
+                }
+                found
+            }) else {
+                continue;
+            };
+            let number = segment.number(index)?;
+            if let SegmentSource::Bin(bin) = &segment.source {
+                // 与 generate_card_number_for_bin_info 一致：避开属性不同的更具体条目
+                if bin_database.lookup(&number).is_none_or(|found| found.bin != *bin) {
+                    continue;
+                }
+            }
+            if history.insert(number.clone()) {
+                return Ok(Some((segment.network.clone(), number)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Segment {
+    /// 段内第 `index` 个卡号
+    fn number(&self, index: u128) -> Result<String, String> {
+        let scale = 10u128.pow(self.free_digits as u32);
+        let (head, tail) = (index / scale, index % scale);
+        let algorithm = self.scheme.algorithm();
+        
+        let prefix = match &self.source {
+            SegmentSource::Pattern(pattern) if !pattern.is_prefix() => {
+                let mut digits: Vec<u32> = pattern.nth(head).chars().filter_map(|c| c.to_digit(10)).collect();
+                match pattern.check_digit_position() {
+                    Some(position) if algorithm.check_length() == 0 => digits[position] = tail as u32,
+                    Some(position) => fill_check_digits(&mut digits, position, algorithm),
+                    None => {}
+                }
+                return Ok(digits_to_string(&digits));
+            }
+            SegmentSource::Pattern(pattern) => pattern.nth(head),
+            SegmentSource::Range(range) => range.format_prefix(range.start + head as u64),
+            SegmentSource::Bin(bin) => bin.clone(),
+        };
+        let payload = if self.free_digits == 0 {
+            prefix
+        } else {
+            format!("{}{:0width$}", prefix, tail, width = self.free_digits)
+        };
+        let check_digits = algorithm.compute(&payload)?;
+        Ok(payload + &check_digits)
+    }
+}
+
+/// 逐张生成卡数据的迭代器，由 [`CreditCardGenerator::generate_card_stream`] 创建
+///
+/// 出错时返回一次 `Err` 后结束。
+pub struct CardStream<'a> {
+    generator: &'a mut CreditCardGenerator,
+    options: GenerateOptions,
+    plan: GenerationPlan,
+    history: CardHistory,
+    generated: usize,
+    failed: bool,
+}
+
+impl CardStream<'_> {
+    /// 已生成的数量
+    pub fn generated(&self) -> usize {
+        self.generated
+    }
+    
+    /// 计划生成的总数量
+    pub fn total(&self) -> usize {
+        self.options.quantity
+    }
+    
+    /// 取回去重历史记录（包含本次生成的卡号）
+    pub fn into_history(self) -> CardHistory {
+        self.history
+    }
+}
+
+impl Iterator for CardStream<'_> {
+    type Item = Result<CardData, String>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.generated >= self.options.quantity {
+            return None;
+        }
+        
+        let card = self.generator.next_card(
+            &self.options,
+            &mut self.plan,
+            &mut self.history,
+            self.generated,
+        );
+        match &card {
+            Ok(_) => self.generated += 1,
+            Err(_) => self.failed = true,
+        }
+        Some(card)
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.failed { 0 } else { self.options.quantity - self.generated };
+        (0, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check_digit::tests::DigitSum;
+    use crate::networks::IinRange;
+    use crate::validator::{validate_card_number_with, ValidationError};
+    
+    #[test]
+    fn test_luhn_checksum() {
+        assert_eq!(CreditCardGenerator::luhn_checksum("4111111111111111"), 0);
+        assert_eq!(CreditCardGenerator::luhn_checksum("5555555555554444"), 0);
+        assert!(CreditCardGenerator::is_luhn_valid("4111111111111111"));
+    }
+    
+    #[test]
+    fn test_generate_card() {
+        let mut generator = CreditCardGenerator::new();
+        let result = generator.generate_card_number("visa", None);
+        assert!(result.is_ok());
+        
+        let card_num = result.unwrap();
+        assert!(card_num.starts_with('4'));
+        assert!(CreditCardGenerator::is_luhn_valid(&card_num));
+    }
+    
+    #[test]
+    fn test_bin_with_placeholder() {
+        let mut generator = CreditCardGenerator::new();
+        // 以 x 结尾的模式长度就是卡号长度：18 位不是 Visa 允许的长度，
+        // 以前会生成 18 位的无效卡号，现在直接报错
+        let err = generator.generate_card_number("visa", Some("4532xxxxxxxxxxxxxx")).unwrap_err();
+        assert!(err.contains("BIN 模式长度 18 不是 Visa 允许的卡号长度"), "{}", err);
+    }
+    
+    #[test]
+    fn test_bin_with_placeholder_full_length() {
+        let mut generator = CreditCardGenerator::new();
+        let card_num = generator.generate_card_number("visa", Some("4532xxxxxxxxxxxxxxx")).unwrap();
+        assert_eq!(card_num.len(), 19);
+        assert!(card_num.starts_with("4532"));
+        assert!(CreditCardGenerator::is_luhn_valid(&card_num));
+    }
+    
+    #[test]
+    fn test_bin_pattern() {
+        let mut generator = CreditCardGenerator::with_seed(10);
+        
+        // 通配符之后的固定数字会保留
+        for _ in 0..20 {
+            let number = generator.generate_card_number("visa", Some("4532x1xx xxxx xx[0-4]x")).unwrap();
+            assert_eq!(number.len(), 16);
+            assert!(number.starts_with("4532"));
+            assert_eq!(&number[5..6], "1");
+            assert!(number[14..15] <= *"4");
+            assert!(CreditCardGenerator::is_luhn_valid(&number));
+        }
+        
+        // 只有一个占位符时不再溢出
+        let number = generator.generate_card_number("visa", Some("411111111111111x")).unwrap();
+        assert_eq!(number, "4111111111111111");
+        
+        // 校验位可以位于任意位置
+        let number = generator.generate_card_number("visa", Some("4111c1111111111x")).unwrap();
+        assert_eq!(number.len(), 16);
+        assert!(CreditCardGenerator::is_luhn_valid(&number));
+        
+        // 前缀模式使用网络默认长度
+        let number = generator.generate_card_number("mastercard", Some("5[1-5]")).unwrap();
+        assert_eq!(number.len(), 16);
+        assert!(CreditCardGenerator::is_luhn_valid(&number));
+        
+        let err = generator.generate_card_number("visa", Some("4[0-")).unwrap_err();
+        assert!(err.contains("没有闭合"), "{}", err);
+    }
+    
+    #[test]
+    fn test_bin_pattern_must_fit_network() {
+        let mut generator = CreditCardGenerator::with_seed(10);
+        
+        // 完整长度的模式须是网络允许的长度
+        for bin in ["x", "4c", "4532xxxxxxxxxxxxxx"] {
+            let err = generator.generate_card_number("visa", Some(bin)).unwrap_err();
+            assert!(err.contains("允许的卡号长度"), "{}: {}", bin, err);
+        }
+        // 固定前缀与占位符都须落在网络的 IIN 区间内
+        for (network, bin) in [("visa", "x{16}"), ("visa", "5xxxxxxxxxxxxxxx"), ("mastercard", "5"), ("mastercard", "2[2-7]")] {
+            let err = generator.generate_card_number(network, Some(bin)).unwrap_err();
+            assert!(err.contains("IIN 区间"), "{}: {}", bin, err);
+        }
+        let err = generator.generate_card_number("visa", Some(&"4".repeat(19))).unwrap_err();
+        assert!(err.contains("超出"), "{}", err);
+        
+        // 随机网络只选择与模式相符的网络
+        let options = GenerateOptions {
+            network: "random".to_string(),
+            bin_code: Some("5[1-5]".to_string()),
+            quantity: 20,
+            ..Default::default()
+        };
+        let cards = generator.generate_card_data(&options).unwrap();
+        assert!(cards.iter().all(|card| card.network == "Mastercard"));
+        let options = GenerateOptions { bin_code: Some("0".to_string()), ..options };
+        assert!(generator.generate_card_data(&options).unwrap_err().contains("没有与 BIN 模式"));
+    }
+    
+    #[test]
+    fn test_reproducible_generator_uses_fixed_date() {
+        let options = GenerateOptions { quantity: 20, ..Default::default() };
+        let generator = CreditCardGenerator::reproducible(5, None);
+        assert_eq!(generator.clock().today(), FixedClock::seeded_default().today());
+        
+        // 结果只取决于种子与日期，与系统日期无关
+        let cards = CreditCardGenerator::reproducible(5, None).generate_card_data(&options).unwrap();
+        let expected = CreditCardGenerator::with_seed(5)
+            .with_clock(FixedClock::seeded_default())
+            .generate_card_data(&options)
+            .unwrap();
+        assert!(cards.iter().zip(&expected).all(|(a, b)| a.number == b.number && a.expiry == b.expiry && a.cvv == b.cvv));
+        
+        let today = FixedClock::from_ymd(2031, 3, 1).unwrap();
+        assert_eq!(CreditCardGenerator::reproducible(5, Some(today)).clock().today(), today.today());
+    }
+    
+    #[test]
+    fn test_seeded_generation_is_reproducible() {
+        let options = GenerateOptions {
+            quantity: 20,
+            include_cvv: true,
+            include_balance: true,
+            currency: Some("USD".to_string()),
+            ..Default::default()
+        };
+        let generate = |seed| {
+            CreditCardGenerator::with_seed(seed)
+                .generate_card_data(&options)
+                .unwrap()
+        };
+        
+        let first = serde_json::to_string(&generate(42)).unwrap();
+        let second = serde_json::to_string(&generate(42)).unwrap();
+        let other = serde_json::to_string(&generate(7)).unwrap();
+        
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_security_codes() {
+        let mut generator = CreditCardGenerator::with_seed(3);
+        let mut options = GenerateOptions {
+            network: "amex".to_string(),
+            quantity: 10,
+            include_cvv: true,
+            ..Default::default()
+        };
+
+        let cards = generator.generate_card_data(&options).unwrap();
+        assert!(cards.iter().all(|c| c.cvv.as_deref().is_some_and(|cvv| cvv.len() == 4)));
+        assert!(cards.iter().all(|c| c.cvv_label.as_deref() == Some("CID")));
+
+        options.cvv_length = Some(3);
+        let cards = generator.generate_card_data(&options).unwrap();
+        assert!(cards.iter().all(|c| c.cvv.as_deref().is_some_and(|cvv| cvv.len() == 3)));
+
+        options.cvv_length = Some(0);
+        let cards = generator.generate_card_data(&options).unwrap();
+        assert!(cards.iter().all(|c| c.cvv.is_none() && c.cvv_label.is_none()));
+
+        options.cvv_length = Some(5);
+        assert!(generator.generate_card_data(&options).is_err());
+    }
+
+    #[test]
+    fn test_cvk_derived_security_codes() {
+        let keys: CvkPair = "0123456789ABCDEFFEDCBA9876543210".parse().unwrap();
+        let options = GenerateOptions {
+            quantity: 20,
+            include_cvv: true,
+            cvk: Some(keys),
+            ..Default::default()
+        };
+
+        let cards = CreditCardGenerator::with_seed(5).generate_card_data(&options).unwrap();
+        for card in &cards {
+            let expiry = format!("{}{}", &card.exp_year[2..], card.exp_month);
+            let cvv = card.cvv.as_deref().unwrap();
+            assert!(crate::cvv::verify_cvv(&card.number, &expiry, "000", &keys, cvv).unwrap(), "{:?}", card);
+        }
+    }
+
+    #[test]
+    fn test_track_data() {
+        let keys: CvkPair = "0123456789ABCDEFFEDCBA9876543210".parse().unwrap();
+        let options = GenerateOptions {
+            quantity: 10,
+            include_tracks: true,
+            service_code: Some("101".to_string()),
+            cvk: Some(keys),
+            ..Default::default()
+        };
+
+        for card in CreditCardGenerator::with_seed(9).generate_card_data(&options).unwrap() {
+            let track1 = card.track1.as_deref().unwrap();
+            let track2 = card.track2.as_deref().unwrap();
+            let expiry = format!("{}{}", &card.exp_year[2..], card.exp_month);
+            assert!(track1.starts_with(&format!("%B{}^{}^{}101", card.number, card.cardholder.as_deref().unwrap(), expiry)));
+            assert!(track2.starts_with(&format!(";{}={}101", card.number, expiry)));
+            assert!(crate::track::is_lrc_valid(track1) && crate::track::is_lrc_valid(track2));
+
+            // 自定义数据末尾的磁条 CVV 可用同一 CVK 校验
+            let (data, _) = track2.split_once('?').unwrap();
+            let cvv = &data[data.len() - 3..];
+            assert!(crate::cvv::verify_cvv(&card.number, &expiry, "101", &keys, cvv).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let mut generator = CreditCardGenerator::with_seed(11).with_clock(FixedClock::from_ymd(2026, 10, 18).unwrap());
+        let cards = generator.generate_card_data(&GenerateOptions {
+            network: "visa".to_string(),
+            quantity: 5,
+            ..Default::default()
+        }).unwrap();
+
+        let mut vault = TokenVault::new();
+        for card in &cards {
+            let record = generator.tokenize(card, &TokenOptions::default(), &mut vault).unwrap();
+            assert_eq!(record.token.len(), card.number.len());
+            assert_ne!(record.token[..6], card.number[..6]);
+            assert!(CreditCardGenerator::is_luhn_valid(&record.token));
+            assert_eq!(generator.registry().detect_network(&record.token).as_deref(), Some("Visa"));
+            assert_eq!(record.token_expiry, "10/29");
+            assert_eq!(vault.detokenize(&record.token).unwrap().pan, card.number);
+
+            // 同一请求方再次令牌化得到同一令牌，其他请求方得到新令牌
+            assert_eq!(generator.tokenize(card, &TokenOptions::default(), &mut vault).unwrap(), record);
+            let other = TokenOptions { requestor_id: Some("40000000002".to_string()), ..Default::default() };
+            assert_ne!(generator.tokenize(card, &other, &mut vault).unwrap().token, record.token);
+        }
+        assert_eq!(vault.len(), 10);
+
+        let options = TokenOptions { token_bin: Some("489537".to_string()), ..Default::default() };
+        let record = generator.tokenize(&cards[0], &options, &mut TokenVault::new()).unwrap();
+        assert!(record.token.starts_with("489537"));
+
+        let options = TokenOptions { token_bin: Some(cards[0].number[..6].to_string()), ..Default::default() };
+        assert!(generator.tokenize(&cards[0], &options, &mut TokenVault::new()).is_err());
+    }
+
+    #[test]
+    fn test_expiry_policy() {
+        let mut generator = CreditCardGenerator::with_seed(13).with_clock(FixedClock::from_ymd(2025, 6, 15).unwrap());
+        let current = 2025 * 12 + 6;
+        let months = |card: &CardData| card.exp_year.parse::<i32>().unwrap() * 12 + card.exp_month.parse::<i32>().unwrap();
+        
+        let expired = generator.generate_card_data(&GenerateOptions {
+            quantity: 10,
+            expiry: Some(ExpiryPolicy::ExpiredBy { months: 3 }),
+            // 设置策略后忽略 exp_month / exp_year
+            exp_month: Some(1),
+            ..Default::default()
+        }).unwrap();
+        assert!(expired.iter().all(|card| months(card) == current - 3));
+        
+        let window = generator.generate_card_data(&GenerateOptions {
+            quantity: 50,
+            expiry: Some(ExpiryPolicy::FutureRange { min_months: 12, max_months: 36 }),
+            ..Default::default()
+        }).unwrap();
+        assert!(window.iter().all(|card| (current + 12..=current + 36).contains(&months(card))));
+        
+        let exact = generator.generate_card_data(&GenerateOptions {
+            expiry: Some(ExpiryPolicy::Exact { month: 2, year: 2019 }),
+            ..Default::default()
+        }).unwrap();
+        assert_eq!(exact[0].expiry, "02/19");
+        
+        assert!(generator.generate_card_data(&GenerateOptions {
+            expiry: Some(ExpiryPolicy::ExpiredBy { months: 0 }),
+            ..Default::default()
+        }).is_err());
+        
+        // 年份须为四位数，月份须在 1-12 之间
+        for (exp_month, exp_year) in [(None, Some(5)), (None, Some(99999)), (Some(13), None)] {
+            let options = GenerateOptions { exp_month, exp_year, ..Default::default() };
+            assert!(generator.generate_card_data(&options).is_err(), "{:?}/{:?}", exp_month, exp_year);
+        }
+    }
+    
+    #[test]
+    fn test_expiry_rolls_over_year_end() {
+        let months = |(month, year): (String, String)| year.parse::<i32>().unwrap() * 12 + month.parse::<i32>().unwrap();
+        
+        // 12 月：默认生成不早于本月，下个月进入次年 1 月
+        let mut december = CreditCardGenerator::with_seed(1).with_clock(FixedClock::from_ymd(2025, 12, 31).unwrap());
+        let current = 2025 * 12 + 12;
+        for _ in 0..200 {
+            let expiry = months(december.generate_expiry(None, None));
+            assert!((current..=current + 8 * 12 + 11).contains(&expiry));
+            assert_eq!(december.generate_expiry(None, Some(2025)), ("12".to_string(), "2025".to_string()));
+            let (month, year) = december.generate_expiry(Some(1), None);
+            assert!((month == "01" && year.as_str() > "2025") || (month == "12" && year == "2025"), "{}/{}", month, year);
+        }
+        let next = ExpiryPolicy::FutureRange { min_months: 1, max_months: 1 };
+        assert_eq!(december.generate_expiry_with(&next).unwrap(), ("01".to_string(), "2026".to_string()));
+        assert_eq!(december.generate_expiry_with(&ExpiryPolicy::EdgeOfMonth).unwrap(), ("12".to_string(), "2025".to_string()));
+        
+        // 1 月：过期一个月回到上一年 12 月，默认生成不会落在上一年
+        let mut january = CreditCardGenerator::with_seed(1).with_clock(FixedClock::from_ymd(2026, 1, 1).unwrap());
+        let expired = ExpiryPolicy::ExpiredBy { months: 1 };
+        assert_eq!(january.generate_expiry_with(&expired).unwrap(), ("12".to_string(), "2025".to_string()));
+        for _ in 0..200 {
+            assert!(january.generate_expiry(None, None).1.as_str() >= "2026");
+        }
+        
+        // 指定的过去日期仍被替换为未来日期（未设置过期策略时）
+        let (month, year) = january.generate_expiry(Some(12), Some(2025));
+        assert!(months((month, year)) > 2026 * 12);
+    }
+    
+    #[test]
+    fn test_card_stream_matches_batch_generation() {
+        let options = GenerateOptions {
+            quantity: 50,
+            include_cvv: true,
+            ..Default::default()
+        };
+        let batch = CreditCardGenerator::with_seed(5).generate_card_data(&options).unwrap();
+        
+        let mut generator = CreditCardGenerator::with_seed(5);
+        let mut stream = generator.generate_card_stream(&options).unwrap();
+        assert_eq!(stream.total(), 50);
+        let first = stream.next().unwrap().unwrap();
+        assert_eq!(stream.generated(), 1);
+        let rest: Vec<CardData> = stream.map(Result::unwrap).collect();
+        
+        assert_eq!(first.number, batch[0].number);
+        assert_eq!(rest.len(), 49);
+        assert!(rest.iter().zip(&batch[1..]).all(|(a, b)| a.number == b.number && a.cvv == b.cvv));
+        
+        // 出错后只返回一次错误
+        let mut stream = generator.generate_card_stream(&GenerateOptions {
+            network: "visa".to_string(),
+            quantity: 3,
+            expiry: Some(ExpiryPolicy::Exact { month: 13, year: 2030 }),
+            ..Default::default()
+        }).unwrap();
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+    }
+    
+    #[test]
+    fn test_parallel_generation_is_reproducible_across_thread_counts() {
+        let options = GenerateOptions {
+            quantity: PARALLEL_CHUNK_SIZE * 2 + 100,
+            include_cvv: true,
+            ..Default::default()
+        };
+        let generate = |threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| CreditCardGenerator::with_seed(42).generate_card_data_parallel(&options).unwrap())
+        };
+        
+        let single = generate(1);
+        let multi = generate(4);
+        assert_eq!(single.len(), options.quantity);
+        assert!(single.iter().zip(&multi).all(|(a, b)| a.number == b.number && a.cvv == b.cvv));
+        assert!(single.iter().all(|card| CreditCardGenerator::is_luhn_valid(&card.number)));
+    }
+    
+    #[test]
+    fn test_parallel_unique_generation() {
+        let options = GenerateOptions {
+            network: "visa".to_string(),
+            quantity: 9000,
+            bin_code: Some("45321234567xxxxx".to_string()),
+            unique: true,
+            ..Default::default()
+        };
+        
+        let cards = CreditCardGenerator::with_seed(3).generate_card_data_parallel(&options).unwrap();
+        let numbers: std::collections::HashSet<_> = cards.iter().map(|c| c.number.as_str()).collect();
+        assert_eq!(numbers.len(), 9000);
+        
+        let again = CreditCardGenerator::with_seed(3).generate_card_data_parallel(&options).unwrap();
+        assert!(cards.iter().zip(&again).all(|(a, b)| a.number == b.number));
+    }
+    
+    #[test]
+    fn test_generate_with_registered_network() {
+        let mut generator = CreditCardGenerator::with_seed(1);
+        let mut jcb = generator.registry().get_network_by_identifier("visa").unwrap().clone();
+        jcb.name = "JCB".to_string();
+        jcb.identifier = "jcb".to_string();
+        jcb.ranges = vec![IinRange::parse("3528-3589").unwrap()];
+        jcb.length = vec![16];
+        generator.registry_mut().register_network(jcb).unwrap();
+        
+        let card = generator.generate_card_number("jcb", None).unwrap();
+        assert!((3528..=3589).contains(&card[..4].parse::<u32>().unwrap()));
+        assert_eq!(card.len(), 16);
+        assert!(CreditCardGenerator::is_luhn_valid(&card));
+    }
+    
+    #[test]
+    fn test_alternative_check_digit_algorithms() {
+        let mut registry = CardNetworkRegistry::empty();
+        registry.register_check_digit(CheckDigitScheme::new("digit_sum", DigitSum)).unwrap();
+        registry.load_toml_str(
+            r#"
+            [[networks]]
+            name = "Gift"
+            identifier = "gift"
+            ranges = ["98"]
+            length = [16]
+            cvv_length = 0
+            check_digit = "damm"
+            
+            [[networks]]
+            name = "Loyalty"
+            identifier = "loyalty"
+            ranges = ["71"]
+            length = [12]
+            cvv_length = 0
+            check_digit = "verhoeff"
+            
+            [[networks]]
+            name = "Store"
+            identifier = "store"
+            ranges = ["72"]
+            length = [14]
+            cvv_length = 0
+            check_digit = "mod97_10"
+            
+            [[networks]]
+            name = "Fleet"
+            identifier = "fleet"
+            ranges = ["73"]
+            length = [15]
+            cvv_length = 0
+            check_digit = "digit_sum"
+            "#,
+        )
+        .unwrap();
+        let mut generator = CreditCardGenerator::with_seed(4).with_registry(registry);
+        
+        for network in generator.registry().networks().to_vec() {
+            let algorithm = network.check_digit.algorithm();
+            // 随机生成、以 x 结尾的模式（校验位在末尾）、校验位在第 3 位的模式
+            let prefix = network.ranges[0].to_string();
+            let patterns = [
+                None,
+                Some(format!("{}1{}", prefix, "x".repeat(network.length[0] - 3))),
+                Some(format!("{}c{}", prefix, "x".repeat(network.length[0] - 3))),
+            ];
+            for bin in patterns {
+                let number = generator.generate_card_number(&network.identifier, bin.as_deref()).unwrap();
+                assert_eq!(number.len(), network.length[0]);
+                assert!(algorithm.is_valid(&number), "{} {}", algorithm.name(), number);
+                
+                let result = validate_card_number_with(&number, generator.registry(), generator.bin_database());
+                assert!(result.valid, "{} {}", number, result.reason);
+            }
+        }
+        
+        // 两位校验位：72 + 10 位随机数字 + 2 位校验位
+        let options = GenerateOptions { network: "store".to_string(), ..Default::default() };
+        assert_eq!(generator.keyspace(&options).unwrap(), 10u128.pow(10));
+        
+        let result = validate_card_number_with("9800000000000001", generator.registry(), generator.bin_database());
+        assert_eq!(result.error, Some(ValidationError::CheckDigitFailed { algorithm: "Damm".to_string() }));
+        assert_eq!(result.reason, "Damm 校验失败");
+    }
+    
+    #[test]
+    fn test_keyspace_respects_check_digit_length() {
+        // 无校验位的网络前缀可以占满整个卡号，两位校验位的网络少两位随机数字
+        let registry = CardNetworkRegistry::from_toml_str(
+            r#"
+            [[networks]]
+            name = "Full"
+            identifier = "full"
+            ranges = ["123456789012"]
+            length = [12]
+            cvv_length = 0
+            check_digit = "none"
+            
+            [[networks]]
+            name = "Store"
+            identifier = "store"
+            ranges = ["72"]
+            length = [14]
+            cvv_length = 0
+            check_digit = "mod97_10"
+            "#,
+        )
+        .unwrap();
+        let generator = CreditCardGenerator::with_seed(1).with_registry(registry);
+        let keyspace = |network: &str, bin: Option<&str>| {
+            let options = GenerateOptions {
+                network: network.to_string(),
+                bin_code: bin.map(str::to_string),
+                unique: true,
+                ..Default::default()
+            };
+            generator.keyspace(&options).unwrap()
+        };
+        
+        assert_eq!(keyspace("full", None), 1);
+        assert_eq!(keyspace("full", Some("123456789012")), 1);
+        assert_eq!(keyspace("store", Some("7212")), 10u128.pow(8));
+        assert_eq!(keyspace("store", Some("72123456789xxx")), 10);
+        assert_eq!(keyspace("random", None), 1 + 10u128.pow(10));
+    }
+    
+    #[test]
+    fn test_every_iin_range_roundtrips() {
+        let registry = CardNetworkRegistry::builtin();
+        let is_candidate = |number: &str, identifier: &str| {
+            registry.detect_candidates(number).iter().any(|c| c.identifier == identifier)
+        };
+        
+        for network in registry.networks() {
+            // 区间边界上的每种长度都能被识别回同一网络（重叠区间中作为候选项之一）
+            for range in &network.ranges {
+                for prefix in [range.start, range.end] {
+                    for &length in &network.length {
+                        let mut number = range.format_prefix(prefix);
+                        number.push_str(&"0".repeat(length - number.len() - 1));
+                        let checksum = CreditCardGenerator::luhn_checksum(&format!("{}0", number));
+                        number.push_str(&((10 - checksum) % 10).to_string());
+                        
+                        assert!(is_candidate(&number, &network.identifier), "{}", number);
+                    }
+                }
+            }
+            
+            // 随机生成覆盖每个区间，且生成结果都能识别回同一网络
+            let mut generator = CreditCardGenerator::with_seed(5);
+            let mut hit = vec![false; network.ranges.len()];
+            for _ in 0..500 {
+                let number = generator.generate_card_number(&network.identifier, None).unwrap();
+                assert!(is_candidate(&number, &network.identifier), "{}", number);
+                for (i, range) in network.ranges.iter().enumerate() {
+                    hit[i] |= range.contains(&number);
+                }
+            }
+            assert!(hit.iter().all(|&h| h), "{} 有未生成的区间", network.identifier);
+        }
+    }
+    
+    #[test]
+    fn test_generated_cards_carry_bin_info() {
+        let mut generator = CreditCardGenerator::with_seed(3);
+        let cards = generator
+            .generate_card_data(&GenerateOptions {
+                network: "visa".to_string(),
+                quantity: 3,
+                bin_code: Some("457173xxxxxxxxxx".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        
+        for card in cards {
+            let info = card.bin_info.expect("457173 在内置 BIN 表中");
+            assert_eq!(info.country, "FR");
+        }
+    }
+    
+    #[test]
+    fn test_generate_by_bin_criteria() {
+        use crate::bin_database::{CardSegment, CardType};
+        
+        let mut generator = CreditCardGenerator::with_seed(11);
+        let cards = generator
+            .generate_card_data(&GenerateOptions {
+                network: "visa".to_string(),
+                quantity: 20,
+                criteria: BinCriteria {
+                    country: Some("FR".to_string()),
+                    card_type: Some(CardType::Debit),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .unwrap();
+        
+        for card in &cards {
+            let info = card.bin_info.as_ref().unwrap();
+            assert_eq!(card.network, "Visa");
+            assert_eq!(info.country, "FR");
+            assert_eq!(info.card_type, CardType::Debit);
+            assert!(CreditCardGenerator::is_luhn_valid(&card.number));
+        }
+        
+        let cards = generator
+            .generate_card_data(&GenerateOptions {
+                quantity: 20,
+                criteria: BinCriteria {
+                    segment: Some(CardSegment::Commercial),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(cards.iter().all(|c| c.bin_info.as_ref().unwrap().segment == CardSegment::Commercial));
+    }
+    
+    #[test]
+    fn test_bin_criteria_errors() {
+        let mut generator = CreditCardGenerator::with_seed(11);
+        let criteria = BinCriteria {
+            country: Some("ZZ".to_string()),
+            ..Default::default()
+        };
+        let options = GenerateOptions { criteria: criteria.clone(), ..Default::default() };
+        assert!(generator.generate_card_data(&options).is_err());
+        
+        let options = GenerateOptions {
+            bin_code: Some("4111".to_string()),
+            criteria,
+            ..Default::default()
+        };
+        assert!(generator.generate_card_data(&options).is_err());
+    }
+    
+    #[test]
+    fn test_unique_generation() {
+        let mut generator = CreditCardGenerator::with_seed(9);
+        let options = GenerateOptions {
+            network: "visa".to_string(),
+            quantity: 100,
+            bin_code: Some("453212345678xxxx".to_string()),
+            unique: true,
+            ..Default::default()
+        };
+        
+        // 453212345678xxxx：3 位随机数字 + 校验位，共 1000 个卡号
+        assert_eq!(generator.keyspace(&options).unwrap(), 1000);
+        
+        let mut history = CardHistory::new();
+        let cards = generator.generate_card_data_with_history(&options, &mut history).unwrap();
+        let numbers: std::collections::HashSet<_> = cards.iter().map(|c| c.number.clone()).collect();
+        assert_eq!(numbers.len(), 100);
+        assert_eq!(history.len(), 100);
+        
+        // 第二批不会与历史记录重复
+        let cards = generator.generate_card_data_with_history(&options, &mut history).unwrap();
+        assert!(cards.iter().all(|c| !numbers.contains(&c.number)));
+        assert_eq!(history.len(), 200);
+    }
+    
+    #[test]
+    fn test_unique_generation_rejects_oversized_requests() {
+        let mut generator = CreditCardGenerator::with_seed(9);
+        let mut options = GenerateOptions {
+            network: "visa".to_string(),
+            quantity: 11,
+            bin_code: Some("45321234567890xx".to_string()),
+            unique: true,
+            ..Default::default()
+        };
+        
+        assert_eq!(generator.keyspace(&options).unwrap(), 10);
+        let err = generator.generate_card_data(&options).unwrap_err();
+        assert!(err.contains("最多只有 10 个"), "{}", err);
+        
+        // 可用卡号刚好够用时全部生成
+        options.quantity = 10;
+        let cards = generator.generate_card_data(&options).unwrap();
+        let numbers: std::collections::HashSet<_> = cards.iter().map(|c| c.number.as_str()).collect();
+        assert_eq!(numbers.len(), 10);
+        
+        // 检查数量时扣除历史记录中的卡号
+        let mut history = CardHistory::new();
+        options.quantity = 4;
+        generator.generate_card_data_with_history(&options, &mut history).unwrap();
+        options.quantity = 7;
+        let err = generator.generate_card_data_with_history(&options, &mut history).unwrap_err();
+        assert!(err.contains("最多只有 6 个"), "{}", err);
+        options.quantity = 6;
+        generator.generate_card_data_with_history(&options, &mut history).unwrap();
+        assert_eq!(history.len(), 10);
+    }
+    
+    #[test]
+    fn test_unique_generation_ignores_unrelated_history() {
+        // 共用的历史文件里其他网络、其他 BIN 的卡号不占用当前条件的可用数量
+        let mut generator = CreditCardGenerator::with_seed(5);
+        let mut history = CardHistory::new();
+        let mastercard = GenerateOptions {
+            network: "mastercard".to_string(),
+            quantity: 2000,
+            bin_code: Some("555555".to_string()),
+            unique: true,
+            ..Default::default()
+        };
+        generator.generate_card_data_with_history(&mastercard, &mut history).unwrap();
+        
+        // 411111111111xxxx：3 位随机数字 + 校验位，共 1000 个卡号
+        let mut visa = GenerateOptions {
+            network: "visa".to_string(),
+            quantity: 10,
+            bin_code: Some("411111111111xxxx".to_string()),
+            unique: true,
+            ..Default::default()
+        };
+        assert_eq!(generator.keyspace(&visa).unwrap(), 1000);
+        generator.generate_card_data_with_history(&visa, &mut history).unwrap();
+        assert_eq!(history.len(), 2010);
+        
+        // 只扣除同一 BIN 下的 10 个
+        visa.quantity = 991;
+        let err = generator.generate_card_data_with_history(&visa, &mut history).unwrap_err();
+        assert!(err.contains("最多只有 990 个"), "{}", err);
+        visa.quantity = 990;
+        generator.generate_card_data_with_history(&visa, &mut history).unwrap();
+        assert_eq!(history.len(), 3000);
+    }
+    
+    #[test]
+    fn test_unique_generation_exhausts_keyspace() {
+        // 45321234567xxxxx：4 位随机数字 + 校验位，共 10000 个卡号，全部取出也不会失败
+        let options = GenerateOptions {
+            network: "visa".to_string(),
+            quantity: 10000,
+            bin_code: Some("45321234567xxxxx".to_string()),
+            unique: true,
+            ..Default::default()
+        };
+        
+        let cards = CreditCardGenerator::with_seed(1).generate_card_data(&options).unwrap();
+        let numbers: std::collections::HashSet<_> = cards.iter().map(|c| c.number.as_str()).collect();
+        assert_eq!(numbers.len(), 10000);
+        assert!(cards.iter().all(|c| c.number.starts_with("45321234567") && CreditCardGenerator::is_luhn_valid(&c.number)));
+        
+        let parallel = CreditCardGenerator::with_seed(1).generate_card_data_parallel(&options).unwrap();
+        let numbers: std::collections::HashSet<_> = parallel.iter().map(|c| c.number.as_str()).collect();
+        assert_eq!(numbers.len(), 10000);
+        
+        // 网络区间、BIN 表条目与无校验位的网络同样可以取尽
+        let mut registry = CardNetworkRegistry::builtin();
+        let mut small = registry.get_network_by_identifier("visa").unwrap().clone();
+        small.identifier = "small".to_string();
+        small.ranges = vec![IinRange::new(4000_0000_0000, 4000_0000_0009, 12).unwrap()];
+        small.length = vec![15];
+        small.check_digit = CheckDigitScheme::builtin("none").unwrap();
+        registry.register_network(small).unwrap();
+        let mut short = registry.get_network_by_identifier("visa").unwrap().clone();
+        short.identifier = "short".to_string();
+        short.ranges = vec![IinRange::new(9, 9, 1).unwrap()];
+        short.length = vec![10];
+        registry.register_network(short).unwrap();
+        let bins = BinDatabase::from_csv_str("bin,network,issuer,country,card_type,tier\n90000001,short,Test,ZZ,prepaid,gift\n").unwrap();
+        let mut generator = CreditCardGenerator::with_seed(2).with_registry(registry).with_bin_database(bins);
+        
+        let options = GenerateOptions { network: "small".to_string(), quantity: 10_000, bin_code: None, ..options };
+        let cards = generator.generate_card_data(&options).unwrap();
+        let numbers: std::collections::HashSet<_> = cards.iter().map(|c| c.number.as_str()).collect();
+        assert_eq!(numbers.len(), 10_000);
+        
+        let options = GenerateOptions {
+            network: "short".to_string(),
+            quantity: 10,
+            criteria: BinCriteria { country: Some("ZZ".to_string()), ..Default::default() },
+            ..options
+        };
+        let cards = generator.generate_card_data(&options).unwrap();
+        let numbers: std::collections::HashSet<_> = cards.iter().map(|c| c.number.as_str()).collect();
+        assert_eq!(numbers.len(), 10);
+        assert!(cards.iter().all(|c| c.number.starts_with("90000001")));
+    }
+}
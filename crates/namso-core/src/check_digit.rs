@@ -0,0 +1,254 @@
+//! 校验位算法：Luhn（模 10）、Verhoeff、Damm、ISO 7064 Mod 97-10，以及无校验位。
+//!
+//! 网络定义中的 `check_digit`（见 [`crate::networks::CheckDigitScheme`]）按名称选择生成、
+//! 验证、令牌化与保格式加密时使用的算法，礼品卡、积分卡等不使用 Luhn 的号码也能生成。
+//! 实现 [`CheckDigitAlgorithm`] 并通过 [`crate::networks::CardNetworkRegistry::register_check_digit`]
+//! 注册后，定义文件即可按名称引用自定义算法。
+
+use crate::card_generator::CreditCardGenerator;
+
+/// 校验位算法，校验位位于号码末尾
+pub trait CheckDigitAlgorithm: Send + Sync {
+    /// 算法名称，用于提示
+    fn name(&self) -> &'static str;
+
+    /// 校验位位数（Mod 97-10 为 2 位，无校验位为 0）
+    fn check_length(&self) -> usize {
+        1
+    }
+
+    /// 为不含校验位的数字串计算校验位，含非数字字符时返回错误
+    fn compute(&self, payload: &str) -> Result<String, String>;
+
+    /// 完整号码（末尾含校验位）是否通过校验
+    fn is_valid(&self, number: &str) -> bool {
+        let check_length = self.check_length();
+        if number.len() <= check_length || !number.chars().all(|c| c.is_ascii_digit()) {
+            return false;
+        }
+        let (payload, check) = number.split_at(number.len() - check_length);
+        self.compute(payload).is_ok_and(|computed| computed == check)
+    }
+}
+
+/// 逐位取出数字，含非数字字符时返回错误
+pub fn digits(payload: &str) -> Result<Vec<u8>, String> {
+    payload
+        .bytes()
+        .map(|b| if b.is_ascii_digit() { Ok(b - b'0') } else { Err(format!("校验位只能由数字计算: {}", payload)) })
+        .collect()
+}
+
+/// Luhn（模 10）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Luhn;
+
+impl CheckDigitAlgorithm for Luhn {
+    fn name(&self) -> &'static str {
+        "Luhn"
+    }
+
+    fn compute(&self, payload: &str) -> Result<String, String> {
+        digits(payload)?;
+        let checksum = CreditCardGenerator::luhn_checksum(&format!("{}0", payload));
+        Ok(((10 - checksum) % 10).to_string())
+    }
+
+    fn is_valid(&self, number: &str) -> bool {
+        !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) && CreditCardGenerator::is_luhn_valid(number)
+    }
+}
+
+/// 无校验位，最后一位为普通数字
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoCheckDigit;
+
+impl CheckDigitAlgorithm for NoCheckDigit {
+    fn name(&self) -> &'static str {
+        "None"
+    }
+
+    fn check_length(&self) -> usize {
+        0
+    }
+
+    fn compute(&self, payload: &str) -> Result<String, String> {
+        digits(payload)?;
+        Ok(String::new())
+    }
+}
+
+/// Verhoeff（基于二面体群 D5），可发现全部单个数字错误与相邻数字换位
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Verhoeff;
+
+const VERHOEFF_D: [[u8; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+const VERHOEFF_P: [[u8; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+    [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+const VERHOEFF_INV: [u8; 10] = [0, 4, 3, 2, 1, 5, 6, 7, 8, 9];
+
+impl Verhoeff {
+    /// 从右向左累积，`offset` 为最右一位的置换序号（计算校验位时为 1）
+    fn checksum(number: &str, offset: usize) -> Result<u8, String> {
+        Ok(digits(number)?
+            .into_iter()
+            .rev()
+            .enumerate()
+            .fold(0, |c, (i, d)| VERHOEFF_D[c as usize][VERHOEFF_P[(i + offset) % 8][d as usize] as usize]))
+    }
+}
+
+impl CheckDigitAlgorithm for Verhoeff {
+    fn name(&self) -> &'static str {
+        "Verhoeff"
+    }
+
+    fn compute(&self, payload: &str) -> Result<String, String> {
+        Ok(VERHOEFF_INV[Self::checksum(payload, 1)? as usize].to_string())
+    }
+
+    fn is_valid(&self, number: &str) -> bool {
+        number.len() > 1 && Self::checksum(number, 0) == Ok(0)
+    }
+}
+
+/// Damm（全反对称拟群），可发现全部单个数字错误与相邻数字换位
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Damm;
+
+const DAMM_TABLE: [[u8; 10]; 10] = [
+    [0, 3, 1, 7, 5, 9, 8, 6, 4, 2],
+    [7, 0, 9, 2, 1, 5, 4, 8, 6, 3],
+    [4, 2, 0, 6, 8, 7, 1, 3, 5, 9],
+    [1, 7, 5, 0, 9, 8, 3, 4, 2, 6],
+    [6, 1, 2, 3, 0, 4, 5, 9, 7, 8],
+    [3, 6, 7, 4, 2, 0, 9, 5, 8, 1],
+    [5, 8, 6, 9, 7, 2, 0, 1, 3, 4],
+    [8, 9, 4, 5, 3, 6, 2, 0, 1, 7],
+    [9, 4, 3, 8, 6, 1, 7, 2, 0, 5],
+    [2, 5, 8, 1, 4, 3, 6, 7, 9, 0],
+];
+
+impl CheckDigitAlgorithm for Damm {
+    fn name(&self) -> &'static str {
+        "Damm"
+    }
+
+    fn compute(&self, payload: &str) -> Result<String, String> {
+        Ok(digits(payload)?
+            .into_iter()
+            .fold(0, |interim, d| DAMM_TABLE[interim as usize][d as usize])
+            .to_string())
+    }
+}
+
+/// ISO 7064 Mod 97-10，两位校验位（IBAN 使用的算法）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Mod97_10;
+
+impl Mod97_10 {
+    fn remainder(number: &str) -> Result<u32, String> {
+        Ok(digits(number)?.into_iter().fold(0, |r, d| (r * 10 + d as u32) % 97))
+    }
+}
+
+impl CheckDigitAlgorithm for Mod97_10 {
+    fn name(&self) -> &'static str {
+        "ISO 7064 Mod 97-10"
+    }
+
+    fn check_length(&self) -> usize {
+        2
+    }
+
+    fn compute(&self, payload: &str) -> Result<String, String> {
+        Ok(format!("{:02}", 98 - Self::remainder(&format!("{}00", payload))?))
+    }
+
+    fn is_valid(&self, number: &str) -> bool {
+        number.len() > 2 && Self::remainder(number) == Ok(1)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// 测试用的自定义算法：各位数字之和的个位作为校验位
+    pub(crate) struct DigitSum;
+
+    impl CheckDigitAlgorithm for DigitSum {
+        fn name(&self) -> &'static str {
+            "Digit Sum"
+        }
+
+        fn compute(&self, payload: &str) -> Result<String, String> {
+            Ok((digits(payload)?.iter().map(|&d| d as u32).sum::<u32>() % 10).to_string())
+        }
+    }
+
+    #[test]
+    fn test_known_check_digits() {
+        assert_eq!(Luhn.compute("411111111111111").unwrap(), "1");
+        assert_eq!(Verhoeff.compute("236").unwrap(), "3");
+        assert_eq!(Verhoeff.compute("12345").unwrap(), "1");
+        assert_eq!(Damm.compute("572").unwrap(), "4");
+        assert_eq!(Mod97_10.compute("794").unwrap(), "44");
+        // IBAN GB82 WEST 1234 5698 7654 32：字母换成数字、国家码与校验位移到末尾
+        assert!(Mod97_10.is_valid("3214282912345698765432161182"));
+
+        let algorithms: [&dyn CheckDigitAlgorithm; 6] = [&Luhn, &NoCheckDigit, &Verhoeff, &Damm, &Mod97_10, &DigitSum];
+        for algorithm in algorithms {
+            let number = format!("90012345{}", algorithm.compute("90012345").unwrap());
+            assert!(algorithm.is_valid(&number), "{} {}", algorithm.name(), number);
+            assert!(!algorithm.is_valid("12a4"), "{}", algorithm.name());
+            // 非数字输入返回错误而不是溢出
+            for payload in ["12a4", "٣٤", "4111 1111"] {
+                assert!(algorithm.compute(payload).is_err(), "{} {}", algorithm.name(), payload);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detects_single_digit_errors() {
+        let algorithms: [&dyn CheckDigitAlgorithm; 4] = [&Luhn, &Verhoeff, &Damm, &Mod97_10];
+        for algorithm in algorithms {
+            let number = format!("7012345678{}", algorithm.compute("7012345678").unwrap());
+            for position in 0..number.len() {
+                for digit in b'0'..=b'9' {
+                    let mut changed = number.clone().into_bytes();
+                    if changed[position] == digit {
+                        continue;
+                    }
+                    changed[position] = digit;
+                    let changed = String::from_utf8(changed).unwrap();
+                    assert!(!algorithm.is_valid(&changed), "{} {}", algorithm.name(), changed);
+                }
+            }
+        }
+        // Verhoeff 与 Damm 还能发现相邻数字换位
+        assert!(!Verhoeff.is_valid("2336"));
+        assert!(!Damm.is_valid("5274"));
+    }
+}
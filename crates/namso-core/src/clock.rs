@@ -0,0 +1,72 @@
+//! 时钟抽象：过期日期以它提供的“今天”为基准，测试与固定种子运行可注入固定时钟。
+
+use chrono::{Local, NaiveDate};
+use std::fmt;
+use std::str::FromStr;
+
+/// 提供当前日期
+pub trait Clock: Send + Sync + fmt::Debug {
+    fn today(&self) -> NaiveDate;
+}
+
+/// 系统时钟（本地时区），生成器默认使用
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Local::now().date_naive()
+    }
+}
+
+/// 固定日期的时钟，结果不随运行时间与时区变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(NaiveDate);
+
+impl FixedClock {
+    pub fn new(date: NaiveDate) -> Self {
+        Self(date)
+    }
+
+    /// [`crate::card_generator::CreditCardGenerator::reproducible`] 未指定日期时使用的日期（2025-01-01）；
+    /// 命令行与桌面端不使用这个默认值，固定种子时要求调用方明确给出日期
+    pub fn seeded_default() -> Self {
+        Self::from_ymd(2025, 1, 1).expect("固定日期有效")
+    }
+
+    /// 按年月日创建，日期无效时返回 `None`
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month, day).map(Self)
+    }
+}
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        self.0
+    }
+}
+
+/// 解析 `YYYY-MM-DD`
+impl FromStr for FixedClock {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+            .map(Self)
+            .map_err(|_| format!("日期格式无效（应为 YYYY-MM-DD）: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock() {
+        let clock: FixedClock = "2025-12-31".parse().unwrap();
+        assert_eq!(clock.today(), NaiveDate::from_ymd_opt(2025, 12, 31).unwrap());
+        assert_eq!(FixedClock::from_ymd(2025, 12, 31), Some(clock));
+        assert!(FixedClock::from_ymd(2025, 2, 30).is_none());
+        assert!("2025/12/31".parse::<FixedClock>().is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! 卡验证值计算与校验：按 Visa CVV 算法，由卡号、有效期、服务码和测试用 CVK 对（双倍长 DES 密钥）
+//! 计算 CVV / CVC（磁条）、iCVV（芯片）与 CVV2（卡面）。
+
+use des::cipher::generic_array::GenericArray;
+use des::cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use des::Des;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 卡验证值种类，决定计算时使用的服务码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CvvKind {
+    /// 磁条 CVV / CVC，使用卡上的服务码
+    Cvv,
+    /// 芯片 iCVV，服务码固定为 999
+    Icvv,
+    /// 卡面 CVV2 / CVC2，服务码固定为 000
+    Cvv2,
+}
+
+impl CvvKind {
+    /// 计算时使用的服务码，磁条 CVV 必须提供卡上的服务码
+    pub fn service_code<'a>(&self, card_service_code: Option<&'a str>) -> Result<&'a str, String> {
+        match self {
+            CvvKind::Cvv => card_service_code.ok_or_else(|| "计算磁条 CVV 需要服务码".to_string()),
+            CvvKind::Icvv => Ok("999"),
+            CvvKind::Cvv2 => Ok("000"),
+        }
+    }
+}
+
+/// 一对 CVK（CVK A / CVK B），以 32 位十六进制字符串表示
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CvkPair {
+    a: u64,
+    b: u64,
+}
+
+impl CvkPair {
+    pub fn new(a: u64, b: u64) -> Self {
+        Self { a, b }
+    }
+
+    /// 解析 32 位十六进制密钥（CVK A 在前），忽略空格
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let hex: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if hex.len() != 32 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("CVK 必须是 32 位十六进制: {}", s));
+        }
+        Ok(Self {
+            a: u64::from_str_radix(&hex[..16], 16).map_err(|e| e.to_string())?,
+            b: u64::from_str_radix(&hex[16..], 16).map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+/// 不在调试输出中泄露密钥
+impl fmt::Debug for CvkPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CvkPair(..)")
+    }
+}
+
+impl fmt::Display for CvkPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016X}{:016X}", self.a, self.b)
+    }
+}
+
+impl FromStr for CvkPair {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for CvkPair {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<CvkPair> for String {
+    fn from(keys: CvkPair) -> Self {
+        keys.to_string()
+    }
+}
+
+/// 计算 3 位卡验证值，`expiry` 为 YYMM，`service_code` 为 3 位服务码
+pub fn compute_cvv(pan: &str, expiry: &str, service_code: &str, keys: &CvkPair) -> Result<String, String> {
+    compute_cvv_with_length(pan, expiry, service_code, keys, 3)
+}
+
+/// 计算指定位数（3 或 4）的卡验证值，4 位用于 Amex 等四位安全码的网络
+pub fn compute_cvv_with_length(
+    pan: &str,
+    expiry: &str,
+    service_code: &str,
+    keys: &CvkPair,
+    length: usize,
+) -> Result<String, String> {
+    if !(3..=4).contains(&length) {
+        return Err(format!("卡验证值必须是 3 或 4 位: {}", length));
+    }
+    if !(12..=19).contains(&pan.len()) || !is_digits(pan) {
+        return Err(format!("卡号无效: {}", pan));
+    }
+    if expiry.len() != 4 || !is_digits(expiry) {
+        return Err(format!("有效期必须是 YYMM 格式: {}", expiry));
+    }
+    if service_code.len() != 3 || !is_digits(service_code) {
+        return Err(format!("服务码必须是 3 位数字: {}", service_code));
+    }
+
+    // 卡号 + 有效期 + 服务码右补 0 到 32 位，分成两个 64 位数据块
+    let data = format!("{:0<32}", format!("{}{}{}", pan, expiry, service_code));
+    let block1 = u64::from_str_radix(&data[..16], 16).map_err(|e| e.to_string())?;
+    let block2 = u64::from_str_radix(&data[16..32], 16).map_err(|e| e.to_string())?;
+
+    // 块 1 用 CVK A 加密，与块 2 异或后再做 3DES（A 加密、B 解密、A 加密）
+    let cvk_a = Des::new(&keys.a.to_be_bytes().into());
+    let cvk_b = Des::new(&keys.b.to_be_bytes().into());
+    let mut block = GenericArray::from(block1.to_be_bytes());
+    cvk_a.encrypt_block(&mut block);
+    let mut block = GenericArray::from((u64::from_be_bytes(block.into()) ^ block2).to_be_bytes());
+    cvk_a.encrypt_block(&mut block);
+    cvk_b.decrypt_block(&mut block);
+    cvk_a.encrypt_block(&mut block);
+
+    // 十进制化：先按顺序取数字，不够时再把 A-F 转为 0-5
+    let hex = format!("{:016X}", u64::from_be_bytes(block.into()));
+    let digits = hex.chars().filter(|c| c.is_ascii_digit());
+    let letters = hex.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| (b'0' + (c as u8 - b'A')) as char);
+    Ok(digits.chain(letters).take(length).collect())
+}
+
+/// 校验卡验证值，位数按传入的值（3 或 4 位）确定
+pub fn verify_cvv(pan: &str, expiry: &str, service_code: &str, keys: &CvkPair, cvv: &str) -> Result<bool, String> {
+    let expected = compute_cvv_with_length(pan, expiry, service_code, keys, cvv.len())?;
+    Ok(expected == cvv)
+}
+
+fn is_digits(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_cvv_known_values() {
+        let keys: CvkPair = "0123456789ABCDEF FEDCBA9876543210".parse().unwrap();
+        assert_eq!(compute_cvv("4123456789012345", "8701", "101", &keys).unwrap(), "561");
+
+        let cvv2 = compute_cvv("4123456789012345", "8701", CvvKind::Cvv2.service_code(None).unwrap(), &keys).unwrap();
+        assert!(verify_cvv("4123456789012345", "8701", "000", &keys, &cvv2).unwrap());
+        assert!(!verify_cvv("4123456789012345", "8702", "000", &keys, &cvv2).unwrap());
+
+        let cid = compute_cvv_with_length("378282246310005", "3012", "000", &keys, 4).unwrap();
+        assert_eq!(cid.len(), 4);
+        assert!(cid.starts_with(&compute_cvv("378282246310005", "3012", "000", &keys).unwrap()));
+    }
+
+    #[test]
+    fn test_rejects_invalid_input() {
+        let keys = CvkPair::new(0x0123456789ABCDEF, 0xFEDCBA9876543210);
+        assert_eq!(keys.to_string(), "0123456789ABCDEFFEDCBA9876543210");
+        assert!(compute_cvv("4123456789012345", "87-1", "101", &keys).is_err());
+        assert!(compute_cvv("4123456789012345", "8701", "10", &keys).is_err());
+        assert!(verify_cvv("4123456789012345", "8701", "101", &keys, "56").is_err());
+        assert!(CvvKind::Cvv.service_code(None).is_err());
+        assert!("0123".parse::<CvkPair>().is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! EMV 测试数据：把生成的卡编码为 READ RECORD 模板（标签 `70`），以及解码粘贴的 EMV 数据。
+
+use crate::card_generator::CardData;
+use crate::tlv::{self, to_hex, Tlv};
+use crate::track::DEFAULT_SERVICE_CODE;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// 应用使用控制：允许国内、国际的现金、商品、服务交易
+const APPLICATION_USAGE_CONTROL: [u8; 2] = [0xFF, 0x00];
+
+/// 常用 EMV 标签名称
+pub fn tag_name(tag: u32) -> Option<&'static str> {
+    Some(match tag {
+        0x4F => "Application Identifier (AID)",
+        0x50 => "Application Label",
+        0x57 => "Track 2 Equivalent Data",
+        0x5A => "Application PAN",
+        0x5F20 => "Cardholder Name",
+        0x5F24 => "Application Expiration Date",
+        0x5F25 => "Application Effective Date",
+        0x5F28 => "Issuer Country Code",
+        0x5F30 => "Service Code",
+        0x5F34 => "PAN Sequence Number",
+        0x6F => "FCI Template",
+        0x70 => "READ RECORD Response Template",
+        0x77 => "Response Message Template Format 2",
+        0x82 => "Application Interchange Profile",
+        0x84 => "Dedicated File Name",
+        0x8C => "CDOL1",
+        0x8D => "CDOL2",
+        0x94 => "Application File Locator",
+        0x9F07 => "Application Usage Control",
+        0x9F08 => "Application Version Number",
+        0x9F0D => "IAC - Default",
+        0x9F0E => "IAC - Denial",
+        0x9F0F => "IAC - Online",
+        0x9F1F => "Track 1 Discretionary Data",
+        0x9F26 => "Application Cryptogram",
+        0x9F27 => "Cryptogram Information Data",
+        0x9F36 => "Application Transaction Counter",
+        0x9F6B => "Track 2 Data",
+        _ => return None,
+    })
+}
+
+/// 按网络名称选择 AID，未知网络返回 `None`
+fn application_id(network: &str) -> Option<&'static str> {
+    Some(match network.to_lowercase().as_str() {
+        "visa" => "A0000000031010",
+        "mastercard" => "A0000000041010",
+        "american express" | "amex" => "A00000002501",
+        "discover" | "diners club" | "diners" => "A0000001523010",
+        "unionpay" => "A000000333010101",
+        "jcb" => "A0000000651010",
+        _ => return None,
+    })
+}
+
+/// 把一张卡编码为 READ RECORD 模板（`70`），包含 PAN、有效期、PAN 序号、
+/// 二磁道等效数据、持卡人姓名、服务码、应用使用控制以及已知网络的 AID 与应用标签
+pub fn card_record(card: &CardData) -> Result<Tlv, String> {
+    let year: i32 = card.exp_year.parse().map_err(|_| format!("过期年份无效: {}", card.exp_year))?;
+    let month: u32 = card.exp_month.parse().map_err(|_| format!("过期月份无效: {}", card.exp_month))?;
+    let last_day = NaiveDate::from_ymd_opt(year + month as i32 / 12, month % 12 + 1, 1)
+        .and_then(|next_month| next_month.pred_opt())
+        .ok_or_else(|| format!("过期日期无效: {}/{}", card.exp_month, card.exp_year))?;
+    let service_code = card.service_code.as_deref().unwrap_or(DEFAULT_SERVICE_CODE);
+
+    let mut tags = Vec::new();
+    if let Some(aid) = application_id(&card.network) {
+        tags.push(Tlv::new(0x4F, tlv::from_hex(aid)?));
+        tags.push(Tlv::new(0x50, card.network.to_uppercase().into_bytes()));
+    }
+    tags.push(Tlv::new(0x57, bcd(&track2_equivalent(card, service_code))?));
+    tags.push(Tlv::new(0x5A, bcd(&card.number)?));
+    if let Some(name) = &card.cardholder {
+        tags.push(Tlv::new(0x5F20, name.as_bytes()));
+    }
+    tags.push(Tlv::new(0x5F24, bcd(&last_day.format("%y%m%d").to_string())?));
+    tags.push(Tlv::new(0x5F30, bcd(&format!("0{}", service_code))?));
+    tags.push(Tlv::new(0x5F34, vec![0x00]));
+    tags.push(Tlv::new(0x9F07, APPLICATION_USAGE_CONTROL));
+
+    Ok(Tlv::constructed(0x70, &tags))
+}
+
+/// 二磁道等效数据：已生成磁道时沿用 Track 2（`=` 换为 `D`，去掉起止符与 LRC），
+/// 否则由卡号、有效期与服务码组成
+fn track2_equivalent(card: &CardData, service_code: &str) -> String {
+    if let Some(track2) = &card.track2 {
+        let data = track2.trim_start_matches(';');
+        let data = data.split('?').next().unwrap_or(data);
+        return data.replace('=', "D");
+    }
+    let year = &card.exp_year[card.exp_year.len().saturating_sub(2)..];
+    format!("{}D{}{}{}", card.number, year, card.exp_month, service_code)
+}
+
+/// 压缩 BCD，奇数位时末尾补 `F`
+fn bcd(digits: &str) -> Result<Vec<u8>, String> {
+    let mut hex = digits.to_string();
+    if !hex.len().is_multiple_of(2) {
+        hex.push('F');
+    }
+    tlv::from_hex(&hex)
+}
+
+/// 解码后的一个 EMV 标签
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmvTag {
+    pub tag: String,
+    pub name: Option<String>,
+    /// 原始值（十六进制）
+    pub value: String,
+    /// 可读形式：数字类标签去掉 `F` 填充，文本类标签按 ASCII 显示
+    pub text: Option<String>,
+    /// 构造型标签的子标签
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<EmvTag>,
+}
+
+/// 解析十六进制 EMV 数据并逐个描述标签
+pub fn decode(hex: &str) -> Result<Vec<EmvTag>, String> {
+    describe(&tlv::parse_hex(hex)?)
+}
+
+fn describe(tlvs: &[Tlv]) -> Result<Vec<EmvTag>, String> {
+    tlvs.iter()
+        .map(|tlv| {
+            let hex = to_hex(&tlv.value);
+            let text = match tlv.tag {
+                0x57 | 0x5A | 0x5F24 | 0x5F25 | 0x5F28 | 0x5F30 | 0x5F34 => Some(hex.trim_end_matches('F').to_string()),
+                0x50 | 0x5F20 => String::from_utf8(tlv.value.clone()).ok(),
+                _ => None,
+            };
+            Ok(EmvTag {
+                tag: tlv.tag_hex(),
+                name: tag_name(tlv.tag).map(str::to_string),
+                value: hex,
+                text,
+                children: describe(&tlv.children()?)?,
+            })
+        })
+        .collect()
+}
+
+/// 从 EMV 数据中取出卡号：优先使用 `5A`，否则取 `57` 中分隔符 `D` 之前的部分
+pub fn pan(tlvs: &[Tlv]) -> Option<String> {
+    tlv::find(tlvs, 0x5A)
+        .map(|tlv| to_hex(&tlv.value).trim_end_matches('F').to_string())
+        .or_else(|| track2_pan(tlvs))
+}
+
+/// `57` 中的卡号
+pub fn track2_pan(tlvs: &[Tlv]) -> Option<String> {
+    tlv::find(tlvs, 0x57).and_then(|tlv| to_hex(&tlv.value).split('D').next().map(str::to_string))
+}
+
+/// `5F24` 中的有效期，返回 `(月份, 年份)`
+pub fn expiry(tlvs: &[Tlv]) -> Option<(u32, i32)> {
+    let hex = to_hex(&tlv::find(tlvs, 0x5F24)?.value);
+    let date = NaiveDate::parse_from_str(&hex, "%y%m%d").ok()?;
+    Some((date.month(), date.year()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_card() -> CardData {
+        CardData {
+            number: "4111111111111111".to_string(),
+            network: "Visa".to_string(),
+            exp_month: "02".to_string(),
+            exp_year: "2028".to_string(),
+            expiry: "02/28".to_string(),
+            cvv: None,
+            cvv_label: None,
+            balance: None,
+            currency: None,
+            bin: "411111".to_string(),
+            bin_info: None,
+            cardholder: Some("ZHANG/WEI".to_string()),
+            service_code: Some("201".to_string()),
+            track1: None,
+            track2: Some(";4111111111111111=28022011000012?5".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_card_record_roundtrip() {
+        let record = card_record(&test_card()).unwrap();
+        let tlvs = tlv::parse(&record.encode()).unwrap();
+
+        assert_eq!(pan(&tlvs).as_deref(), Some("4111111111111111"));
+        assert_eq!(track2_pan(&tlvs).as_deref(), Some("4111111111111111"));
+        assert_eq!(expiry(&tlvs), Some((2, 2028)));
+        assert_eq!(to_hex(&tlv::find(&tlvs, 0x5F24).unwrap().value), "280229");
+        assert_eq!(to_hex(&tlv::find(&tlvs, 0x57).unwrap().value), "4111111111111111D28022011000012F");
+        assert_eq!(tlv::find(&tlvs, 0x4F).unwrap().value, tlv::from_hex("A0000000031010").unwrap());
+
+        let tags = decode(&to_hex(&record.encode())).unwrap();
+        assert_eq!(tags[0].tag, "70");
+        let name = tags[0].children.iter().find(|t| t.tag == "5F20").unwrap();
+        assert_eq!(name.text.as_deref(), Some("ZHANG/WEI"));
+        assert_eq!(name.name.as_deref(), Some("Cardholder Name"));
+    }
+
+    #[test]
+    fn test_card_record_without_tracks() {
+        let mut card = test_card();
+        card.number = "378282246310005".to_string();
+        card.network = "Unknown".to_string();
+        card.exp_month = "12".to_string();
+        card.track2 = None;
+        card.cardholder = None;
+
+        let tlvs = tlv::parse(&card_record(&card).unwrap().encode()).unwrap();
+        assert_eq!(to_hex(&tlv::find(&tlvs, 0x5A).unwrap().value), "378282246310005F");
+        assert_eq!(pan(&tlvs).as_deref(), Some("378282246310005"));
+        assert_eq!(expiry(&tlvs), Some((12, 2028)));
+        assert!(tlv::find(&tlvs, 0x4F).is_none());
+        assert!(tlv::find(&tlvs, 0x5F20).is_none());
+    }
+}
@@ -0,0 +1,201 @@
+//! 过期日期策略：未来区间、固定日期、已过期 N 个月、本月到期。
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// 未来区间与已过期月数的上限（100 年）
+pub const MAX_MONTHS: u32 = 1200;
+
+/// 允许的到期年份，年份须为四位数，磁道与导出格式只保留后两位
+pub const YEAR_RANGE: RangeInclusive<i32> = 2000..=2199;
+
+/// 过期日期策略，月份偏移都以当前年月为基准（0 表示本月）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExpiryPolicy {
+    /// 在 `min_months..=max_months` 个月后随机到期
+    FutureRange { min_months: u32, max_months: u32 },
+    /// 固定年月，允许过去的日期
+    Exact { month: u32, year: i32 },
+    /// 已于 `months` 个月前过期（至少为 1）
+    ExpiredBy { months: u32 },
+    /// 本月到期，用于测试月末边界
+    EdgeOfMonth,
+}
+
+impl ExpiryPolicy {
+    /// 以当前年月为基准选择到期年月，返回 `(月份, 年份)`
+    pub fn resolve<R: Rng + ?Sized>(
+        &self,
+        current_year: i32,
+        current_month: u32,
+        rng: &mut R,
+    ) -> Result<(u32, i32), String> {
+        let offset = match *self {
+            ExpiryPolicy::FutureRange { min_months, max_months } => {
+                if min_months > max_months {
+                    return Err(format!("过期区间无效: {} 个月大于 {} 个月", min_months, max_months));
+                }
+                check_months(max_months)?;
+                rng.gen_range(min_months..=max_months) as i32
+            }
+            ExpiryPolicy::Exact { month, year } => {
+                if !(1..=12).contains(&month) {
+                    return Err(format!("过期月份无效: {}", month));
+                }
+                check_year(year)?;
+                return Ok((month, year));
+            }
+            ExpiryPolicy::ExpiredBy { months } => {
+                if months == 0 {
+                    return Err("已过期月数必须至少为 1".to_string());
+                }
+                check_months(months)?;
+                -(months as i32)
+            }
+            ExpiryPolicy::EdgeOfMonth => 0,
+        };
+
+        let (month, year) = add_months(current_year, current_month, offset)
+            .ok_or_else(|| format!("到期日期超出范围: {}-{:02} 偏移 {} 个月", current_year, current_month, offset))?;
+        check_year(year)?;
+        Ok((month, year))
+    }
+}
+
+/// 到期年份须在 [`YEAR_RANGE`] 内
+pub(crate) fn check_year(year: i32) -> Result<(), String> {
+    if !YEAR_RANGE.contains(&year) {
+        return Err(format!(
+            "到期年份必须在 {}-{} 之间: {}",
+            YEAR_RANGE.start(), YEAR_RANGE.end(), year
+        ));
+    }
+    Ok(())
+}
+
+fn check_months(months: u32) -> Result<(), String> {
+    if months > MAX_MONTHS {
+        return Err(format!("过期月数不能超过 {} 个月: {}", MAX_MONTHS, months));
+    }
+    Ok(())
+}
+
+/// 年月加减月份，返回 `(月份, 年份)`，溢出时返回 `None`
+pub(crate) fn add_months(year: i32, month: u32, offset: i32) -> Option<(u32, i32)> {
+    let total = year.checked_mul(12)?
+        .checked_add(i32::try_from(month).ok()? - 1)?
+        .checked_add(offset)?;
+    Some((total.rem_euclid(12) as u32 + 1, total.div_euclid(12)))
+}
+
+impl fmt::Display for ExpiryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpiryPolicy::FutureRange { min_months, max_months } => {
+                write!(f, "future:{}-{}", min_months, max_months)
+            }
+            ExpiryPolicy::Exact { month, year } => write!(f, "exact:{}-{:02}", year, month),
+            ExpiryPolicy::ExpiredBy { months } => write!(f, "expired:{}", months),
+            ExpiryPolicy::EdgeOfMonth => write!(f, "edge"),
+        }
+    }
+}
+
+/// 解析命令行写法：`future:12-36`、`exact:2030-01`、`expired:3`、`edge`
+impl FromStr for ExpiryPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("无法解析过期策略: {}", s);
+        let s = s.trim();
+        let (kind, value) = s.split_once(':').unwrap_or((s, ""));
+
+        match kind.to_lowercase().as_str() {
+            "future" => {
+                let (min, max) = value.split_once('-').ok_or_else(invalid)?;
+                Ok(ExpiryPolicy::FutureRange {
+                    min_months: min.trim().parse().map_err(|_| invalid())?,
+                    max_months: max.trim().parse().map_err(|_| invalid())?,
+                })
+            }
+            "exact" => {
+                let (year, month) = value.split_once('-').ok_or_else(invalid)?;
+                Ok(ExpiryPolicy::Exact {
+                    month: month.trim().parse().map_err(|_| invalid())?,
+                    year: year.trim().parse().map_err(|_| invalid())?,
+                })
+            }
+            "expired" => Ok(ExpiryPolicy::ExpiredBy {
+                months: value.trim().parse().map_err(|_| invalid())?,
+            }),
+            "edge" if value.is_empty() => Ok(ExpiryPolicy::EdgeOfMonth),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_resolve_policies() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..100 {
+            let (month, year) = ExpiryPolicy::FutureRange { min_months: 12, max_months: 36 }
+                .resolve(2025, 6, &mut rng)
+                .unwrap();
+            let offset = (year - 2025) * 12 + month as i32 - 6;
+            assert!((12..=36).contains(&offset), "{}/{}", month, year);
+        }
+
+        let exact = ExpiryPolicy::Exact { month: 3, year: 2020 };
+        assert_eq!(exact.resolve(2025, 6, &mut rng).unwrap(), (3, 2020));
+        assert_eq!(ExpiryPolicy::ExpiredBy { months: 7 }.resolve(2025, 6, &mut rng).unwrap(), (11, 2024));
+        assert_eq!(ExpiryPolicy::EdgeOfMonth.resolve(2025, 6, &mut rng).unwrap(), (6, 2025));
+
+        assert!(ExpiryPolicy::FutureRange { min_months: 5, max_months: 1 }.resolve(2025, 6, &mut rng).is_err());
+        assert!(ExpiryPolicy::Exact { month: 13, year: 2030 }.resolve(2025, 6, &mut rng).is_err());
+        assert!(ExpiryPolicy::ExpiredBy { months: 0 }.resolve(2025, 6, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_resolve_rejects_out_of_range() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for policy in ["future:2147483000-2147483647", "future:0-1201", "expired:3000000000", "expired:1201", "exact:5-01", "exact:99999-12"] {
+            let policy: ExpiryPolicy = policy.parse().unwrap();
+            assert!(policy.resolve(2025, 6, &mut rng).is_err(), "{}", policy);
+        }
+        // 区间上限本身可用，结果年份仍须在允许范围内
+        let (_, year) = ExpiryPolicy::FutureRange { min_months: 1200, max_months: 1200 }.resolve(2025, 6, &mut rng).unwrap();
+        assert_eq!(year, 2125);
+        assert!(ExpiryPolicy::ExpiredBy { months: 1200 }.resolve(2025, 6, &mut rng).is_err());
+        assert!(ExpiryPolicy::EdgeOfMonth.resolve(i32::MAX, 12, &mut rng).is_err());
+        assert_eq!(add_months(i32::MAX, 12, 1), None);
+    }
+
+    #[test]
+    fn test_parse_policies() {
+        for policy in [
+            ExpiryPolicy::FutureRange { min_months: 12, max_months: 36 },
+            ExpiryPolicy::Exact { month: 1, year: 2030 },
+            ExpiryPolicy::ExpiredBy { months: 3 },
+            ExpiryPolicy::EdgeOfMonth,
+        ] {
+            assert_eq!(policy.to_string().parse::<ExpiryPolicy>().unwrap(), policy);
+        }
+
+        let json: ExpiryPolicy = serde_json::from_str(r#"{"type": "expired_by", "months": 2}"#).unwrap();
+        assert_eq!(json, ExpiryPolicy::ExpiredBy { months: 2 });
+        assert!("future:12".parse::<ExpiryPolicy>().is_err());
+        assert!("someday".parse::<ExpiryPolicy>().is_err());
+    }
+}
@@ -0,0 +1,541 @@
+//! 卡数据多格式导出。
+
+use crate::card_generator::CardData;
+use crate::emv;
+use crate::tlv::to_hex;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
+use std::fs::File;
+use std::io::{BufWriter, Cursor, Write};
+use std::path::Path;
+
+/// 新增的列追加在末尾，按列序号读取旧文件的程序不受影响
+const CSV_HEADER: [&str; 10] = [
+    "number", "network", "exp_month", "exp_year", "expiry",
+    "cvv", "balance", "currency", "bin", "cvv_label",
+];
+
+/// 安全码名称，旧数据没有记录时为 `CVV`
+fn cvv_label(card: &CardData) -> &str {
+    card.cvv_label.as_deref().unwrap_or("CVV")
+}
+
+/// 按格式名称导出（PIPE / CSV / JSON / XML / SQL / CARD / TRACK / EMV，不区分大小写）
+pub fn export_cards_as(cards: &[CardData], format: &str) -> Result<String, String> {
+    match format.to_uppercase().as_str() {
+        "PIPE" => Ok(export_to_pipe(cards)),
+        "CSV" => export_to_csv(cards),
+        "JSON" => export_to_json(cards),
+        "XML" => export_to_xml(cards),
+        "SQL" => Ok(export_to_sql(cards, "test_cards")),
+        "CARD" => Ok(export_to_card(cards)),
+        "TRACK" => export_to_track(cards),
+        "EMV" => export_to_emv(cards),
+        _ => Err(format!("不支持的导出格式: {}", format)),
+    }
+}
+
+/// 导出为 PIPE 格式：`卡号|MM/YY|安全码|安全码名称`
+///
+/// 安全码名称（CVV2 / CVC2 / CID / CVN2 等）放在最后一列，按前三列读取的工具不受影响；
+/// 没有安全码时只有前两列。
+pub fn export_to_pipe(cards: &[CardData]) -> String {
+    cards
+        .iter()
+        .map(pipe_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn pipe_line(card: &CardData) -> String {
+    let mut parts = vec![card.number.clone()];
+    let year = &card.exp_year[card.exp_year.len().saturating_sub(2)..];
+    parts.push(format!("{}/{}", card.exp_month, year));
+    if let Some(cvv) = &card.cvv {
+        parts.push(cvv.clone());
+        parts.push(cvv_label(card).to_string());
+    }
+    parts.join("|")
+}
+
+/// 导出为 CSV 格式
+pub fn export_to_csv(cards: &[CardData]) -> Result<String, String> {
+    let mut wtr = csv::Writer::from_writer(vec![]);
+    
+    // 写入表头
+    wtr.write_record(CSV_HEADER).map_err(|e| e.to_string())?;
+    
+    // 写入数据
+    for card in cards {
+        write_csv_record(&mut wtr, card)?;
+    }
+    
+    let data = wtr.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(data).map_err(|e| e.to_string())
+}
+
+fn write_csv_record<W: Write>(wtr: &mut csv::Writer<W>, card: &CardData) -> Result<(), String> {
+    wtr.write_record([
+        card.number.as_str(),
+        &card.network,
+        &card.exp_month,
+        &card.exp_year,
+        &card.expiry,
+        card.cvv.as_deref().unwrap_or(""),
+        &card.balance.map(|b| b.to_string()).unwrap_or_default(),
+        card.currency.as_deref().unwrap_or(""),
+        &card.bin,
+        if card.cvv.is_some() { cvv_label(card) } else { "" },
+    ]).map_err(|e| e.to_string())
+}
+
+/// 导出为 JSON 格式
+pub fn export_to_json(cards: &[CardData]) -> Result<String, String> {
+    serde_json::to_string_pretty(cards).map_err(|e| e.to_string())
+}
+
+/// 导出为 XML 格式
+pub fn export_to_xml(cards: &[CardData]) -> Result<String, String> {
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    
+    // XML 声明
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+        .map_err(|e| e.to_string())?;
+    
+    // 根元素
+    writer.write_event(Event::Start(BytesStart::new("cards")))
+        .map_err(|e| e.to_string())?;
+    
+    for card in cards {
+        write_xml_card(&mut writer, card)?;
+    }
+    
+    // 关闭根元素
+    writer.write_event(Event::End(BytesEnd::new("cards")))
+        .map_err(|e| e.to_string())?;
+    
+    let result = writer.into_inner().into_inner();
+    String::from_utf8(result).map_err(|e| e.to_string())
+}
+
+fn write_xml_card<W: Write>(writer: &mut Writer<W>, card: &CardData) -> Result<(), String> {
+    // card 元素
+    writer.write_event(Event::Start(BytesStart::new("card")))
+        .map_err(|e| e.to_string())?;
+    
+    // 写入各字段
+    write_xml_element(writer, "number", &card.number)?;
+    write_xml_element(writer, "network", &card.network)?;
+    write_xml_element(writer, "exp_month", &card.exp_month)?;
+    write_xml_element(writer, "exp_year", &card.exp_year)?;
+    write_xml_element(writer, "expiry", &card.expiry)?;
+    
+    if let Some(cvv) = &card.cvv {
+        // 安全码名称作为属性：<cvv label="CID">1234</cvv>
+        writer.write_event(Event::Start(BytesStart::new("cvv").with_attributes([("label", cvv_label(card))])))
+            .map_err(|e| e.to_string())?;
+        writer.write_event(Event::Text(BytesText::new(cvv)))
+            .map_err(|e| e.to_string())?;
+        writer.write_event(Event::End(BytesEnd::new("cvv")))
+            .map_err(|e| e.to_string())?;
+    }
+    
+    if let Some(balance) = card.balance {
+        write_xml_element(writer, "balance", &balance.to_string())?;
+    }
+    
+    if let Some(currency) = &card.currency {
+        write_xml_element(writer, "currency", currency)?;
+    }
+    
+    write_xml_element(writer, "bin", &card.bin)?;
+    
+    // 关闭 card 元素
+    writer.write_event(Event::End(BytesEnd::new("card")))
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn write_xml_element<W: Write>(writer: &mut Writer<W>, name: &str, content: &str) -> Result<(), String> {
+    writer.write_event(Event::Start(BytesStart::new(name)))
+        .map_err(|e| e.to_string())?;
+    writer.write_event(Event::Text(BytesText::new(content)))
+        .map_err(|e| e.to_string())?;
+    writer.write_event(Event::End(BytesEnd::new(name)))
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 导出为 SQL 格式
+pub fn export_to_sql(cards: &[CardData], table_name: &str) -> String {
+    let mut sql_lines = sql_create_table(table_name);
+    
+    // 插入数据
+    for card in cards {
+        sql_lines.push(sql_insert(card, table_name));
+    }
+    
+    sql_lines.join("\n")
+}
+
+/// 建表语句，末尾带一个空行
+fn sql_create_table(table_name: &str) -> Vec<String> {
+    let mut sql_lines = vec![];
+    
+    // 创建表结构
+    sql_lines.push(format!("CREATE TABLE IF NOT EXISTS {} (", table_name));
+    sql_lines.push("    number VARCHAR(20),".to_string());
+    sql_lines.push("    network VARCHAR(50),".to_string());
+    sql_lines.push("    exp_month VARCHAR(4),".to_string());
+    sql_lines.push("    exp_year VARCHAR(4),".to_string());
+    sql_lines.push("    expiry VARCHAR(10),".to_string());
+    sql_lines.push("    cvv VARCHAR(4),".to_string());
+    sql_lines.push("    cvv_label VARCHAR(10),".to_string());
+    sql_lines.push("    balance DECIMAL(10,2),".to_string());
+    sql_lines.push("    currency VARCHAR(10),".to_string());
+    sql_lines.push("    bin VARCHAR(20)".to_string());
+    sql_lines.push(");".to_string());
+    sql_lines.push("".to_string());
+    sql_lines
+}
+
+fn sql_insert(card: &CardData, table_name: &str) -> String {
+    let cvv = card.cvv.as_ref().map(|s| format!("'{}'", s)).unwrap_or("NULL".to_string());
+    let label = card.cvv.as_ref().map(|_| format!("'{}'", cvv_label(card))).unwrap_or("NULL".to_string());
+    let balance = card.balance.map(|b| b.to_string()).unwrap_or("NULL".to_string());
+    let currency = card.currency.as_ref()
+        .map(|s| format!("'{}'", s))
+        .unwrap_or("NULL".to_string());
+    
+    format!(
+        "INSERT INTO {} (number, network, exp_month, exp_year, expiry, cvv, cvv_label, balance, currency, bin) VALUES ('{}', '{}', '{}', '{}', '{}', {}, {}, {}, {}, '{}');",
+        table_name,
+        card.number,
+        card.network,
+        card.exp_month,
+        card.exp_year,
+        card.expiry,
+        cvv,
+        label,
+        balance,
+        currency,
+        card.bin
+    )
+}
+
+/// 导出为 CARD 格式（卡片显示）
+pub fn export_to_card(cards: &[CardData]) -> String {
+    cards
+        .iter()
+        .enumerate()
+        .map(|(i, card)| card_block(i, card))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn card_block(index: usize, card: &CardData) -> String {
+    let mut lines = vec![
+        format!("🔖 卡片 #{}", index + 1),
+        format!("💳 卡号: {}", card.number),
+        format!("🌐 网络: {}", card.network),
+        format!("📅 过期: {}", card.expiry),
+    ];
+    
+    if let Some(cvv) = &card.cvv {
+        lines.push(format!("🔒 {}: {}", cvv_label(card), cvv));
+    }
+    
+    if let Some(info) = &card.bin_info {
+        lines.push(format!("🏦 发卡行: {} ({}, {} / {})", info.issuer, info.country, info.card_type, info.tier));
+    }
+    
+    if let Some(balance) = card.balance {
+        let currency = card.currency.as_deref().unwrap_or("USD");
+        lines.push(format!("💰 余额: {} {}", balance, currency));
+    }
+    
+    lines.push("─".repeat(40));
+    lines.join("\n")
+}
+
+/// 导出为 TRACK 格式：每张卡两行，依次为 Track 1 与 Track 2
+pub fn export_to_track(cards: &[CardData]) -> Result<String, String> {
+    Ok(cards
+        .iter()
+        .map(track_lines)
+        .collect::<Result<Vec<_>, _>>()?
+        .join("\n"))
+}
+
+fn track_lines(card: &CardData) -> Result<String, String> {
+    match (&card.track1, &card.track2) {
+        (Some(track1), Some(track2)) => Ok(format!("{}\n{}", track1, track2)),
+        _ => Err(format!("卡号 {} 没有磁道数据，生成时需开启磁道", card.number)),
+    }
+}
+
+/// 导出为 EMV 格式：每张卡一行，为 READ RECORD 模板（标签 `70`）的十六进制 BER-TLV
+pub fn export_to_emv(cards: &[CardData]) -> Result<String, String> {
+    Ok(cards
+        .iter()
+        .map(emv_line)
+        .collect::<Result<Vec<_>, _>>()?
+        .join("\n"))
+}
+
+fn emv_line(card: &CardData) -> Result<String, String> {
+    Ok(to_hex(&emv::card_record(card)?.encode()))
+}
+
+enum Output<W: Write> {
+    Pipe(W),
+    Csv(Box<csv::Writer<W>>),
+    Json(W),
+    Xml(Writer<W>),
+    Sql(W),
+    Card(W),
+    Track(W),
+    Emv(W),
+}
+
+/// 流式导出器：逐张写入卡数据，输出与 [`export_cards_as`] 相同
+pub struct CardExporter<W: Write> {
+    output: Output<W>,
+    written: usize,
+}
+
+impl<W: Write> CardExporter<W> {
+    /// 创建导出器并写入格式头部（CSV 表头、XML 声明、SQL 建表语句）
+    pub fn new(mut writer: W, format: &str) -> Result<Self, String> {
+        let output = match format.to_uppercase().as_str() {
+            "PIPE" => Output::Pipe(writer),
+            "CSV" => {
+                let mut wtr = csv::Writer::from_writer(writer);
+                wtr.write_record(CSV_HEADER).map_err(|e| e.to_string())?;
+                Output::Csv(Box::new(wtr))
+            }
+            "JSON" => Output::Json(writer),
+            "XML" => {
+                let mut xml = Writer::new_with_indent(writer, b' ', 2);
+                xml.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+                    .map_err(|e| e.to_string())?;
+                xml.write_event(Event::Start(BytesStart::new("cards")))
+                    .map_err(|e| e.to_string())?;
+                Output::Xml(xml)
+            }
+            "SQL" => {
+                write!(writer, "{}", sql_create_table("test_cards").join("\n")).map_err(|e| e.to_string())?;
+                Output::Sql(writer)
+            }
+            "CARD" => Output::Card(writer),
+            "TRACK" => Output::Track(writer),
+            "EMV" => Output::Emv(writer),
+            _ => return Err(format!("不支持的导出格式: {}", format)),
+        };
+        Ok(Self { output, written: 0 })
+    }
+    
+    /// 写入一张卡
+    pub fn write_card(&mut self, card: &CardData) -> Result<(), String> {
+        let first = self.written == 0;
+        match &mut self.output {
+            Output::Pipe(w) => write!(w, "{}{}", if first { "" } else { "\n" }, pipe_line(card))
+                .map_err(|e| e.to_string())?,
+            Output::Csv(wtr) => write_csv_record(wtr, card)?,
+            Output::Json(w) => {
+                // 与 serde_json 的美化输出保持一致：元素缩进两格
+                let json = serde_json::to_string_pretty(card).map_err(|e| e.to_string())?;
+                let indented = json.lines().map(|line| format!("  {}", line)).collect::<Vec<_>>().join("\n");
+                write!(w, "{}{}", if first { "[\n" } else { ",\n" }, indented).map_err(|e| e.to_string())?;
+            }
+            Output::Xml(xml) => write_xml_card(xml, card)?,
+            Output::Sql(w) => write!(w, "\n{}", sql_insert(card, "test_cards")).map_err(|e| e.to_string())?,
+            Output::Card(w) => write!(w, "{}{}", if first { "" } else { "\n" }, card_block(self.written, card))
+                .map_err(|e| e.to_string())?,
+            Output::Track(w) => write!(w, "{}{}", if first { "" } else { "\n" }, track_lines(card)?)
+                .map_err(|e| e.to_string())?,
+            Output::Emv(w) => write!(w, "{}{}", if first { "" } else { "\n" }, emv_line(card)?)
+                .map_err(|e| e.to_string())?,
+        }
+        self.written += 1;
+        Ok(())
+    }
+    
+    /// 已写入的卡数量
+    pub fn written(&self) -> usize {
+        self.written
+    }
+    
+    /// 写入格式尾部并刷新，返回底层写入器
+    pub fn finish(self) -> Result<W, String> {
+        let mut writer = match self.output {
+            Output::Csv(wtr) => wtr.into_inner().map_err(|e| e.to_string())?,
+            Output::Json(mut w) => {
+                w.write_all(if self.written == 0 { b"[]" } else { b"\n]" }).map_err(|e| e.to_string())?;
+                w
+            }
+            Output::Xml(mut xml) => {
+                xml.write_event(Event::End(BytesEnd::new("cards")))
+                    .map_err(|e| e.to_string())?;
+                xml.into_inner()
+            }
+            Output::Pipe(w) | Output::Sql(w) | Output::Card(w) | Output::Track(w) | Output::Emv(w) => w,
+        };
+        writer.flush().map_err(|e| e.to_string())?;
+        Ok(writer)
+    }
+}
+
+/// 将卡数据流写入任意写入器，返回写入的数量；遇到生成错误时停止并返回该错误
+pub fn export_cards_to_writer<W, I>(cards: I, format: &str, writer: W) -> Result<usize, String>
+where
+    W: Write,
+    I: IntoIterator<Item = Result<CardData, String>>,
+{
+    let mut exporter = CardExporter::new(writer, format)?;
+    for card in cards {
+        exporter.write_card(&card?)?;
+    }
+    let written = exporter.written();
+    exporter.finish()?;
+    Ok(written)
+}
+
+/// 将卡数据流直接写入文件，不在内存中保留整批结果
+pub fn export_cards_to_file<I>(cards: I, format: &str, path: impl AsRef<Path>) -> Result<usize, String>
+where
+    I: IntoIterator<Item = Result<CardData, String>>,
+{
+    let path = path.as_ref();
+    let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    export_cards_to_writer(cards, format, BufWriter::new(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_generator::CardData;
+    
+    fn create_test_card() -> CardData {
+        CardData {
+            number: "4111111111111111".to_string(),
+            network: "Visa".to_string(),
+            exp_month: "12".to_string(),
+            exp_year: "2025".to_string(),
+            expiry: "12/25".to_string(),
+            cvv: Some("123".to_string()),
+            cvv_label: Some("CVV2".to_string()),
+            balance: Some(1000.50),
+            currency: Some("USD".to_string()),
+            bin: "411111".to_string(),
+            bin_info: None,
+            cardholder: Some("ZHANG/WEI".to_string()),
+            service_code: Some("201".to_string()),
+            track1: Some("%B4111111111111111^ZHANG/WEI^2512201?".to_string()),
+            track2: Some(";4111111111111111=2512201?".to_string()),
+        }
+    }
+    
+    #[test]
+    fn test_export_to_pipe() {
+        let cards = vec![create_test_card()];
+        let result = export_to_pipe(&cards);
+        assert_eq!(result, "4111111111111111|12/25|123|CVV2");
+        
+        // 没有安全码时不输出安全码与名称，旧数据没有名称时为 CVV
+        let card = CardData { cvv: None, ..create_test_card() };
+        assert_eq!(export_to_pipe(&[card]), "4111111111111111|12/25");
+        let card = CardData { cvv_label: None, ..create_test_card() };
+        assert_eq!(export_to_pipe(&[card]), "4111111111111111|12/25|123|CVV");
+        
+        // 年份不足两位时不会越界
+        let card = CardData { exp_year: "5".to_string(), ..create_test_card() };
+        assert!(export_to_pipe(&[card]).contains("|12/5|"));
+    }
+    
+    #[test]
+    fn test_export_to_json() {
+        let cards = vec![create_test_card()];
+        let result = export_to_json(&cards);
+        assert!(result.is_ok());
+        assert!(result.unwrap().contains("4111111111111111"));
+    }
+    
+    #[test]
+    fn test_export_to_csv() {
+        let cards = vec![create_test_card()];
+        let result = export_to_csv(&cards);
+        assert!(result.is_ok());
+        assert!(result.unwrap().contains("number,network"));
+    }
+    
+    #[test]
+    fn test_exports_security_code_label() {
+        let mut card = create_test_card();
+        card.cvv = Some("1234".to_string());
+        card.cvv_label = Some("CID".to_string());
+        let cards = vec![card];
+        
+        assert!(export_to_card(&cards).contains("🔒 CID: 1234"));
+        let csv = export_to_csv(&cards).unwrap();
+        assert!(csv.starts_with("number,network,exp_month,exp_year,expiry,cvv,balance,currency,bin,cvv_label\n"));
+        assert!(csv.contains(",1234,1000.5,USD,411111,CID\n"));
+        assert_eq!(export_to_pipe(&cards), "4111111111111111|12/25|1234|CID");
+        assert!(export_to_json(&cards).unwrap().contains(r#""cvv_label": "CID""#));
+        assert!(export_to_xml(&cards).unwrap().contains(r#"<cvv label="CID">1234</cvv>"#));
+        assert!(export_to_sql(&cards, "t").contains("'1234', 'CID'"));
+    }
+    
+    #[test]
+    fn test_export_to_track() {
+        let cards = vec![create_test_card()];
+        let result = export_to_track(&cards).unwrap();
+        assert_eq!(result.lines().collect::<Vec<_>>(), [
+            "%B4111111111111111^ZHANG/WEI^2512201?",
+            ";4111111111111111=2512201?",
+        ]);
+        
+        let mut card = create_test_card();
+        card.track2 = None;
+        assert!(export_to_track(&[card]).is_err());
+    }
+    
+    #[test]
+    fn test_export_to_emv() {
+        let cards = vec![create_test_card(), create_test_card()];
+        let result = export_to_emv(&cards).unwrap();
+        let lines: Vec<_> = result.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("70"));
+        assert!(lines[0].contains("5A084111111111111111"));
+    }
+    
+    #[test]
+    fn test_export_cards_as() {
+        let cards = vec![create_test_card()];
+        assert_eq!(export_cards_as(&cards, "pipe").unwrap(), export_to_pipe(&cards));
+        assert!(export_cards_as(&cards, "YAML").is_err());
+    }
+    
+    #[test]
+    fn test_streaming_export_matches_export_cards_as() {
+        let mut second = create_test_card();
+        second.number = "5555555555554444".to_string();
+        second.cvv = None;
+        let cards = [create_test_card(), second];
+        
+        for format in ["PIPE", "CSV", "JSON", "XML", "SQL", "CARD", "TRACK", "EMV"] {
+            for batch in [&cards[..0], &cards[..]] {
+                let mut buf = Vec::new();
+                let written = export_cards_to_writer(batch.iter().cloned().map(Ok), format, &mut buf).unwrap();
+                assert_eq!(written, batch.len());
+                assert_eq!(String::from_utf8(buf).unwrap(), export_cards_as(batch, format).unwrap(), "{}", format);
+            }
+        }
+        
+        let failing = [Ok(create_test_card()), Err("生成失败".to_string())];
+        assert!(export_cards_to_writer(failing, "CSV", Vec::new()).is_err());
+    }
+}
+
+
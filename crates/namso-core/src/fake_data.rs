@@ -1,6 +1,9 @@
+//! 虚假用户数据与 Lorem Ipsum 文本生成。
+
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+/// 虚假用户信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FakeUser {
     pub name: String,
@@ -57,6 +60,14 @@ pub fn generate_fake_users(count: usize) -> Vec<FakeUser> {
     users
 }
 
+/// 生成磁道格式的持卡人姓名（拼音大写，`姓/名`，如 `ZHANG/WEIHUA`）
+pub fn generate_cardholder_name<R: Rng + ?Sized>(rng: &mut R) -> String {
+    let first = FIRST_NAMES[rng.gen_range(0..FIRST_NAMES.len())];
+    let middle = MIDDLE_NAMES[rng.gen_range(0..MIDDLE_NAMES.len())];
+    let last = LAST_NAMES[rng.gen_range(0..LAST_NAMES.len())];
+    format!("{}/{}{}", romanize(first), romanize(middle), romanize(last)).to_uppercase()
+}
+
 /// 简单的汉字转拼音（只用于姓名，非常简化版本）
 fn romanize(name: &str) -> String {
     // 这是一个简化版本，实际应用中应该使用专门的拼音库
@@ -145,6 +156,15 @@ mod tests {
         assert_eq!(paragraphs.len(), 3);
     }
     
+    #[test]
+    fn test_generate_cardholder_name() {
+        let name = generate_cardholder_name(&mut rand::thread_rng());
+        let (surname, given) = name.split_once('/').unwrap();
+        assert!(!surname.is_empty() && !given.is_empty());
+        assert!(name.len() <= 26);
+        assert!(name.chars().all(|c| c.is_ascii_uppercase() || c == '/'));
+    }
+    
     #[test]
     fn test_romanize() {
         assert_eq!(romanize("张伟"), "zhangwei");
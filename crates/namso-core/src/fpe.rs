@@ -0,0 +1,419 @@
+//! 卡号保格式加密（NIST SP 800-38G FF1 / FF3-1，AES，十进制）：保留 BIN、重新计算校验位，
+//! 把真实卡号确定性地替换为同网络、同长度的有效卡号，用同一密钥可还原。
+
+use crate::networks::CardNetworkRegistry;
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::{Aes128, Aes192, Aes256};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 十进制
+const RADIX: u128 = 10;
+
+/// 至少保留的 BIN 位数
+const BIN_LENGTH: usize = 6;
+
+/// 加密部分的位数范围：10^6 满足标准对最小域的要求，上限保证中间值不超过 `u128`
+const MIN_DIGITS: usize = 6;
+const MAX_DIGITS: usize = 36;
+
+/// FF3-1 的调整值（tweak）为 56 位
+const FF3_1_TWEAK_LEN: usize = 7;
+
+/// 保格式加密算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FpeMode {
+    #[serde(rename = "ff1")]
+    Ff1,
+    #[serde(rename = "ff3_1")]
+    Ff3_1,
+}
+
+impl fmt::Display for FpeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FpeMode::Ff1 => write!(f, "ff1"),
+            FpeMode::Ff3_1 => write!(f, "ff3-1"),
+        }
+    }
+}
+
+/// 解析 `ff1`、`ff3-1`（也接受 `ff3_1`、`ff31`），不区分大小写
+impl FromStr for FpeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ff1" => Ok(FpeMode::Ff1),
+            "ff3-1" | "ff3_1" | "ff31" => Ok(FpeMode::Ff3_1),
+            _ => Err(format!("不支持的加密模式（应为 ff1 或 ff3-1）: {}", s)),
+        }
+    }
+}
+
+/// AES 密钥（128 / 192 / 256 位），以十六进制字符串表示
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FpeKey(Vec<u8>);
+
+impl FpeKey {
+    /// 解析 32 / 48 / 64 位十六进制密钥，忽略空格
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let hex: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if ![32, 48, 64].contains(&hex.len()) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err("FPE 密钥必须是 32 / 48 / 64 位十六进制（AES-128 / 192 / 256）".to_string());
+        }
+        Ok(Self(parse_hex(&hex)?))
+    }
+}
+
+/// 不在调试输出中泄露密钥
+impl fmt::Debug for FpeKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FpeKey(AES-{})", self.0.len() * 8)
+    }
+}
+
+impl fmt::Display for FpeKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{:02X}", b))
+    }
+}
+
+impl FromStr for FpeKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for FpeKey {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<FpeKey> for String {
+    fn from(key: FpeKey) -> Self {
+        key.to_string()
+    }
+}
+
+/// 按密钥长度选择的 AES 分组密码
+enum Aes {
+    Aes128(Aes128),
+    Aes192(Aes192),
+    Aes256(Aes256),
+}
+
+impl Aes {
+    fn new(key: &[u8]) -> Self {
+        match key.len() {
+            16 => Aes::Aes128(Aes128::new(GenericArray::from_slice(key))),
+            24 => Aes::Aes192(Aes192::new(GenericArray::from_slice(key))),
+            _ => Aes::Aes256(Aes256::new(GenericArray::from_slice(key))),
+        }
+    }
+
+    fn encrypt(&self, block: [u8; 16]) -> [u8; 16] {
+        let mut block = GenericArray::from(block);
+        match self {
+            Aes::Aes128(aes) => aes.encrypt_block(&mut block),
+            Aes::Aes192(aes) => aes.encrypt_block(&mut block),
+            Aes::Aes256(aes) => aes.encrypt_block(&mut block),
+        }
+        block.into()
+    }
+}
+
+/// 卡号保格式加密器
+pub struct PanCipher {
+    mode: FpeMode,
+    aes: Aes,
+    tweak: Vec<u8>,
+}
+
+impl PanCipher {
+    /// 创建加密器；FF3-1 的调整值必须为 7 字节，未提供时为全零
+    pub fn new(mode: FpeMode, key: &FpeKey, tweak: &[u8]) -> Result<Self, String> {
+        let tweak = match mode {
+            FpeMode::Ff1 => tweak.to_vec(),
+            FpeMode::Ff3_1 if tweak.is_empty() => vec![0; FF3_1_TWEAK_LEN],
+            FpeMode::Ff3_1 if tweak.len() == FF3_1_TWEAK_LEN => tweak.to_vec(),
+            FpeMode::Ff3_1 => return Err(format!("FF3-1 的调整值必须是 {} 字节（14 位十六进制）", FF3_1_TWEAK_LEN)),
+        };
+        // FF3-1 使用字节反序的密钥
+        let aes = match mode {
+            FpeMode::Ff1 => Aes::new(&key.0),
+            FpeMode::Ff3_1 => Aes::new(&key.0.iter().rev().copied().collect::<Vec<_>>()),
+        };
+        Ok(Self { mode, aes, tweak })
+    }
+
+    /// 由十六进制调整值创建加密器
+    pub fn with_hex_tweak(mode: FpeMode, key: &FpeKey, tweak: &str) -> Result<Self, String> {
+        Self::new(mode, key, &parse_hex(tweak)?)
+    }
+
+    /// 加密数字串，结果位数相同
+    pub fn encrypt_digits(&self, digits: &str) -> Result<String, String> {
+        let digits = to_digits(digits)?;
+        Ok(from_digits(&match self.mode {
+            FpeMode::Ff1 => self.ff1(&digits, true),
+            FpeMode::Ff3_1 => self.ff3_1(&digits, true),
+        }))
+    }
+
+    /// 解密数字串
+    pub fn decrypt_digits(&self, digits: &str) -> Result<String, String> {
+        let digits = to_digits(digits)?;
+        Ok(from_digits(&match self.mode {
+            FpeMode::Ff1 => self.ff1(&digits, false),
+            FpeMode::Ff3_1 => self.ff3_1(&digits, false),
+        }))
+    }
+
+    /// 加密卡号：保留 BIN（至少 6 位，网络 IIN 区间更长时保留整个前缀），
+    /// 加密其余数字并按网络规则重新计算校验位，结果与原卡号同网络、同长度
+    pub fn encrypt_pan(&self, pan: &str, registry: &CardNetworkRegistry) -> Result<String, String> {
+        self.transform_pan(pan, registry, true)
+    }
+
+    /// 解密由 [`Self::encrypt_pan`] 得到的卡号（校验位重新计算）
+    pub fn decrypt_pan(&self, pan: &str, registry: &CardNetworkRegistry) -> Result<String, String> {
+        self.transform_pan(pan, registry, false)
+    }
+
+    fn transform_pan(&self, pan: &str, registry: &CardNetworkRegistry, encrypt: bool) -> Result<String, String> {
+        let pan: String = pan.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+        if !pan.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("卡号只能包含数字: {}", pan));
+        }
+        let network = registry.detect(&pan).ok_or_else(|| format!("无法识别卡号 {} 的网络", pan))?;
+        let candidate = registry.detect_candidates(&pan).into_iter().next();
+        let bin_length = candidate.map_or(BIN_LENGTH, |c| c.range.prefix_len.max(BIN_LENGTH));
+
+        // 有校验位的网络只加密 BIN 与校验位之间的数字
+        let algorithm = network.check_digit.algorithm();
+        let end = pan.len().saturating_sub(algorithm.check_length());
+        if end < bin_length + MIN_DIGITS {
+            return Err(format!("卡号 {} 除 BIN 与校验位外不足 {} 位，无法保格式加密", pan, MIN_DIGITS));
+        }
+
+        let body = &pan[bin_length..end];
+        let body = if encrypt { self.encrypt_digits(body)? } else { self.decrypt_digits(body)? };
+        let result = format!("{}{}", &pan[..bin_length], body);
+        let check_digits = algorithm.compute(&result)?;
+        Ok(result + &check_digits)
+    }
+
+    /// FF1：10 轮 Feistel，轮函数为 AES-CBC-MAC
+    fn ff1(&self, x: &[u8], encrypt: bool) -> Vec<u8> {
+        let n = x.len();
+        let t = self.tweak.len();
+        let u = n / 2;
+        let v = n - u;
+        let (mut a, mut b) = (x[..u].to_vec(), x[u..].to_vec());
+
+        // b：表示 radix^v 所需字节数；d：轮函数输出字节数
+        let bits = 128 - RADIX.pow(v as u32).leading_zeros() as usize;
+        let b_len = bits.div_ceil(8);
+        let d = 4 * b_len.div_ceil(4) + 4;
+
+        let mut p = vec![1, 2, 1, 0, 0, RADIX as u8, 10, u as u8];
+        p.extend_from_slice(&(n as u32).to_be_bytes());
+        p.extend_from_slice(&(t as u32).to_be_bytes());
+
+        for round in 0..10 {
+            let i = if encrypt { round } else { 9 - round };
+            let m = if i % 2 == 0 { u } else { v };
+            let source = if encrypt { &b } else { &a };
+
+            let mut q = self.tweak.clone();
+            q.resize(t + (16 - (t + b_len + 1) % 16) % 16, 0);
+            q.push(i as u8);
+            q.extend_from_slice(&num(source).to_be_bytes()[16 - b_len..]);
+
+            // R = PRF(P || Q)，再按需扩展为 d 字节
+            let r = p.chunks(16).chain(q.chunks(16)).fold([0u8; 16], |state, chunk| {
+                let mut block = state;
+                block.iter_mut().zip(chunk).for_each(|(s, c)| *s ^= c);
+                self.aes.encrypt(block)
+            });
+            let mut s = r.to_vec();
+            for j in 1..d.div_ceil(16) as u128 {
+                let mut block = r;
+                block.iter_mut().zip(j.to_be_bytes()).for_each(|(s, c)| *s ^= c);
+                s.extend_from_slice(&self.aes.encrypt(block));
+            }
+            s.truncate(d);
+
+            let modulus = RADIX.pow(m as u32);
+            let y = s.iter().fold(0u128, |acc, &byte| (acc * 256 + byte as u128) % modulus);
+            if encrypt {
+                let c = (num(&a) + y) % modulus;
+                a = std::mem::replace(&mut b, digits_of(c, m));
+            } else {
+                let c = (num(&b) + modulus - y) % modulus;
+                b = std::mem::replace(&mut a, digits_of(c, m));
+            }
+        }
+
+        [a, b].concat()
+    }
+
+    /// FF3-1：8 轮 Feistel，数字按反序参与运算
+    fn ff3_1(&self, x: &[u8], encrypt: bool) -> Vec<u8> {
+        let t = &self.tweak;
+        let tweak_left = [t[0], t[1], t[2], t[3] & 0xF0];
+        let tweak_right = [t[4], t[5], t[6], (t[3] & 0x0F) << 4];
+        self.ff3_rounds(x, tweak_left, tweak_right, encrypt)
+    }
+
+    fn ff3_rounds(&self, x: &[u8], tweak_left: [u8; 4], tweak_right: [u8; 4], encrypt: bool) -> Vec<u8> {
+        let n = x.len();
+        let u = n.div_ceil(2);
+        let v = n - u;
+        let (mut a, mut b) = (x[..u].to_vec(), x[u..].to_vec());
+        let reversed = |digits: &[u8]| digits.iter().rev().copied().collect::<Vec<_>>();
+
+        for round in 0..8 {
+            let i = if encrypt { round } else { 7 - round };
+            let (m, w) = if i % 2 == 0 { (u, tweak_right) } else { (v, tweak_left) };
+            let source = if encrypt { &b } else { &a };
+
+            let mut p = [0u8; 16];
+            p[..4].copy_from_slice(&(u32::from_be_bytes(w) ^ i as u32).to_be_bytes());
+            p[4..].copy_from_slice(&num(&reversed(source)).to_be_bytes()[4..]);
+            p.reverse();
+            let mut s = self.aes.encrypt(p);
+            s.reverse();
+
+            let modulus = RADIX.pow(m as u32);
+            let y = u128::from_be_bytes(s) % modulus;
+            if encrypt {
+                let c = (num(&reversed(&a)) + y) % modulus;
+                a = std::mem::replace(&mut b, reversed(&digits_of(c, m)));
+            } else {
+                let c = (num(&reversed(&b)) + modulus - y) % modulus;
+                b = std::mem::replace(&mut a, reversed(&digits_of(c, m)));
+            }
+        }
+
+        [a, b].concat()
+    }
+}
+
+/// 数字序列（高位在前）的数值
+fn num(digits: &[u8]) -> u128 {
+    digits.iter().fold(0, |acc, &d| acc * RADIX + d as u128)
+}
+
+/// 数值的 `len` 位数字表示（高位在前）
+fn digits_of(mut value: u128, len: usize) -> Vec<u8> {
+    let mut digits = vec![0; len];
+    for digit in digits.iter_mut().rev() {
+        *digit = (value % RADIX) as u8;
+        value /= RADIX;
+    }
+    digits
+}
+
+fn to_digits(s: &str) -> Result<Vec<u8>, String> {
+    if !(MIN_DIGITS..=MAX_DIGITS).contains(&s.len()) {
+        return Err(format!("保格式加密的数字串应为 {}-{} 位: {}", MIN_DIGITS, MAX_DIGITS, s));
+    }
+    s.chars()
+        .map(|c| c.to_digit(10).map(|d| d as u8).ok_or_else(|| format!("只能包含数字: {}", s)))
+        .collect()
+}
+
+fn from_digits(digits: &[u8]) -> String {
+    digits.iter().map(|&d| char::from(b'0' + d)).collect()
+}
+
+fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("十六进制数据无效: {}", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_generator::CreditCardGenerator;
+
+    #[test]
+    fn test_ff1_nist_vectors() {
+        let key = FpeKey::parse("2B7E151628AED2A6ABF7158809CF4F3C").unwrap();
+
+        let cipher = PanCipher::new(FpeMode::Ff1, &key, &[]).unwrap();
+        assert_eq!(cipher.encrypt_digits("0123456789").unwrap(), "2433477484");
+        assert_eq!(cipher.decrypt_digits("2433477484").unwrap(), "0123456789");
+
+        let cipher = PanCipher::with_hex_tweak(FpeMode::Ff1, &key, "39383736353433323130").unwrap();
+        assert_eq!(cipher.encrypt_digits("0123456789").unwrap(), "6124200773");
+        assert_eq!(cipher.decrypt_digits("6124200773").unwrap(), "0123456789");
+    }
+
+    #[test]
+    fn test_ff3_vectors() {
+        // FF3 样例（64 位调整值），验证 FF3-1 共用的轮函数
+        let key = FpeKey::parse("EF4359D8D580AA4F7F036D6F04FC6A94").unwrap();
+        let cipher = PanCipher::new(FpeMode::Ff3_1, &key, &[]).unwrap();
+        let x = to_digits("890121234567890000").unwrap();
+        let y = cipher.ff3_rounds(&x, [0xD8, 0xE7, 0x92, 0x0A], [0xFA, 0x33, 0x0A, 0x73], true);
+        assert_eq!(from_digits(&y), "750918814058654607");
+        assert_eq!(cipher.ff3_rounds(&y, [0xD8, 0xE7, 0x92, 0x0A], [0xFA, 0x33, 0x0A, 0x73], false), x);
+
+        // FF3-1（56 位调整值）
+        let key = FpeKey::parse("2DE79D232DF5585D68CE47882AE256D6").unwrap();
+        let cipher = PanCipher::with_hex_tweak(FpeMode::Ff3_1, &key, "CBD09280979564").unwrap();
+        assert_eq!(cipher.encrypt_digits("3992520240").unwrap(), "8901801106");
+        assert_eq!(cipher.decrypt_digits("8901801106").unwrap(), "3992520240");
+    }
+
+    #[test]
+    fn test_pan_roundtrip() {
+        let registry = CardNetworkRegistry::builtin();
+        let key: FpeKey = "2B7E151628AED2A6ABF7158809CF4F3C".parse().unwrap();
+
+        for mode in [FpeMode::Ff1, FpeMode::Ff3_1] {
+            let cipher = PanCipher::new(mode, &key, &[]).unwrap();
+            for pan in ["4111111111111111", "5555555555554444", "378282246310005", "6221261234567890129"] {
+                let masked = cipher.encrypt_pan(pan, &registry).unwrap();
+                assert_ne!(masked, pan);
+                assert_eq!(masked.len(), pan.len());
+                assert_eq!(masked[..6], pan[..6]);
+                assert!(CreditCardGenerator::is_luhn_valid(&masked));
+                assert_eq!(registry.detect_network(&masked), registry.detect_network(pan));
+                assert_eq!(cipher.encrypt_pan(pan, &registry).unwrap(), masked);
+                assert_eq!(cipher.decrypt_pan(&masked, &registry).unwrap(), pan);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_input() {
+        let registry = CardNetworkRegistry::builtin();
+        let key: FpeKey = "2B7E151628AED2A6ABF7158809CF4F3C".parse().unwrap();
+        let cipher = PanCipher::new(FpeMode::Ff1, &key, &[]).unwrap();
+
+        assert!(FpeKey::parse("2B7E").is_err());
+        assert!(PanCipher::with_hex_tweak(FpeMode::Ff3_1, &key, "0011").is_err());
+        assert!(cipher.encrypt_pan("411111111111", &registry).is_err());
+        assert!(cipher.encrypt_pan("9999999999999999", &registry).is_err());
+        assert!(cipher.encrypt_digits("12345").is_err());
+        assert_eq!(format!("{:?}", key), "FpeKey(AES-128)");
+    }
+}
@@ -0,0 +1,95 @@
+//! 已生成卡号的记录，用于跨批次保证卡号不重复。
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// 已生成卡号集合，可持久化为每行一个卡号的文本文件
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CardHistory {
+    numbers: HashSet<String>,
+}
+
+impl CardHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从文件加载，文件不存在时返回空记录
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Self {
+                numbers: content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(String::from)
+                    .collect(),
+            }),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// 保存到文件（按卡号排序，便于比对）
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let mut numbers: Vec<&str> = self.numbers.iter().map(String::as_str).collect();
+        numbers.sort_unstable();
+
+        let mut content = numbers.join("\n");
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        fs::write(path, content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn contains(&self, number: &str) -> bool {
+        self.numbers.contains(number)
+    }
+
+    /// 记录卡号，已存在时返回 `false`
+    pub fn insert(&mut self, number: String) -> bool {
+        self.numbers.insert(number)
+    }
+
+    /// 逐个取出已记录的卡号（无序）
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.numbers.iter().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.numbers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.numbers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_roundtrips_through_file() {
+        let path = std::env::temp_dir().join(format!("namso-history-{}.txt", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        assert!(CardHistory::load(&path).unwrap().is_empty());
+
+        let mut history = CardHistory::new();
+        assert!(history.insert("4111111111111111".to_string()));
+        assert!(!history.insert("4111111111111111".to_string()));
+        history.insert("5555555555554444".to_string());
+        history.save(&path).unwrap();
+
+        let loaded = CardHistory::load(&path).unwrap();
+        assert_eq!(loaded, history);
+        assert!(loaded.contains("5555555555554444"));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+//! NamsoGen 核心库：卡号生成、网络识别、校验、导出与虚假数据。
+//!
+//! 本库不依赖 Tauri 或任何 WebView 组件，桌面应用（`src-tauri`）与
+//! 命令行工具（`namso-cli`）都只是它的使用方，也可以直接在测试工具中依赖：
+//!
+//! ```toml
+//! [dependencies]
+//! namso-core = { path = "crates/namso-core" }
+//! ```
+//!
+//! # 示例
+//!
+//! ```
+//! use namso_core::{export_cards_as, validate_card_number, CreditCardGenerator, GenerateOptions};
+//!
+//! let mut generator = CreditCardGenerator::with_seed(42);
+//! let cards = generator
+//!     .generate_card_data(&GenerateOptions {
+//!         network: "visa".to_string(),
+//!         quantity: 3,
+//!         include_cvv: true,
+//!         ..Default::default()
+//!     })
+//!     .unwrap();
+//!
+//! assert!(cards.iter().all(|card| validate_card_number(&card.number).valid));
+//! let csv = export_cards_as(&cards, "CSV").unwrap();
+//! assert!(csv.starts_with("number,network"));
+//! ```
+//!
+//! # 模块
+//!
+//! - [`card_generator`]：[`CreditCardGenerator`] 与 [`CardData`]，支持固定种子与逐张生成（[`CardStream`]）
+//! - [`bin_pattern`]：BIN 模式（通配符、数字集合、重复与校验位位置）
+//! - [`bin_database`]：离线 BIN 元数据（发卡行、国家、卡类型、产品等级）
+//! - [`networks`]：卡网络注册表，可从 TOML / JSON 加载自定义网络
+//! - [`check_digit`]：校验位算法（Luhn、Verhoeff、Damm、ISO 7064 Mod 97-10、无校验位），按网络以名称选择，可注册自定义算法
+//! - [`cvv`]：由测试 CVK 计算与校验 CVV / iCVV / CVV2
+//! - [`fpe`]：卡号保格式加密（FF1 / FF3-1），保留 BIN 的可逆脱敏
+//! - [`tlv`]：BER-TLV 编码与解析
+//! - [`emv`]：EMV 测试数据（READ RECORD 模板）编码与解码
+//! - [`clock`]：时钟抽象，过期日期以其“今天”为基准，可注入固定时钟
+//! - [`expiry`]：过期日期策略（未来区间、固定日期、已过期、本月到期）
+//! - [`track`]：ISO 7813 Track 1 / Track 2 磁道数据
+//! - [`token`]：网络令牌（令牌卡号、令牌有效期、请求方 ID）与令牌库
+//! - [`validator`]：卡号校验
+//! - [`exporters`]：PIPE / CSV / JSON / XML / SQL / CARD / TRACK / EMV 导出，支持流式写入文件
+//! - [`history`]：已生成卡号记录，用于跨批次去重
+//! - [`fake_data`]：虚假用户与 Lorem Ipsum 文本
+
+pub mod bin_database;
+pub mod bin_pattern;
+pub mod card_generator;
+pub mod check_digit;
+pub mod clock;
+pub mod cvv;
+pub mod emv;
+pub mod expiry;
+pub mod exporters;
+pub mod fake_data;
+pub mod fpe;
+pub mod history;
+pub mod networks;
+pub mod tlv;
+pub mod token;
+pub mod track;
+pub mod validator;
+
+pub use bin_database::{BinCriteria, BinDatabase, BinInfo, CardSegment, CardType};
+pub use bin_pattern::{BinPattern, BinPatternError};
+pub use card_generator::{CardData, CardStream, CreditCardGenerator, GenerateOptions};
+pub use check_digit::CheckDigitAlgorithm;
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use cvv::{compute_cvv, verify_cvv, CvkPair, CvvKind};
+pub use emv::EmvTag;
+pub use expiry::ExpiryPolicy;
+pub use exporters::{export_cards_as, export_cards_to_file, export_cards_to_writer, CardExporter};
+pub use fake_data::{generate_fake_users, generate_lorem_ipsum, FakeUser};
+pub use fpe::{FpeKey, FpeMode, PanCipher};
+pub use history::CardHistory;
+pub use networks::{CardNetwork, CardNetworkRegistry, CheckDigitScheme, IinRange, NetworkCandidate};
+pub use tlv::Tlv;
+pub use token::{TokenOptions, TokenRecord, TokenVault};
+pub use track::TrackData;
+pub use validator::{
+    validate_card_list, validate_card_number, validate_card_number_with, validate_card_with_cvv, validate_cards,
+    validate_card_record, validate_emv_data_with, BatchValidation, CardRecord, FieldValidation, Locale, RecordField,
+    RecordValidation, ValidationError, ValidationResult, ValidationSummary, UNKNOWN_NETWORK,
+};
@@ -0,0 +1,794 @@
+//! 卡网络定义与网络识别。
+//!
+//! 注册表默认包含内置的六个网络，也可以从 TOML / JSON 定义文件加载，
+//! 或在运行时通过 [`CardNetworkRegistry::register_network`] 追加自定义网络。
+
+use crate::check_digit::{CheckDigitAlgorithm, Damm, Luhn, Mod97_10, NoCheckDigit, Verhoeff};
+use crate::validator::ValidationError;
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+/// 内置注册表只构建一次，见 [`CardNetworkRegistry::shared_builtin`]
+static SHARED_BUILTIN: LazyLock<Arc<CardNetworkRegistry>> = LazyLock::new(|| Arc::new(CardNetworkRegistry::builtin()));
+
+/// 按名称引用的校验位算法
+///
+/// 定义文件中写作名称：内置的 `luhn`（默认）、`none`、`verhoeff`、`damm`、`mod97_10`（也可写作 `mod97-10`），
+/// 或通过 [`CardNetworkRegistry::register_check_digit`] 注册的自定义名称。自定义名称在网络注册到注册表时
+/// 才绑定到实际算法。
+#[derive(Clone)]
+pub struct CheckDigitScheme {
+    name: String,
+    /// 尚未绑定的自定义名称为 `None`
+    algorithm: Option<Arc<dyn CheckDigitAlgorithm>>,
+}
+
+impl CheckDigitScheme {
+    /// 以名称与实现创建算法，用于注册自定义算法或直接设置到网络
+    pub fn new(name: impl Into<String>, algorithm: impl CheckDigitAlgorithm + 'static) -> Self {
+        Self { name: name.into(), algorithm: Some(Arc::new(algorithm)) }
+    }
+
+    /// 按名称取内置算法
+    pub fn builtin(name: &str) -> Option<Self> {
+        let (name, algorithm): (&str, Arc<dyn CheckDigitAlgorithm>) = match name {
+            "luhn" => ("luhn", Arc::new(Luhn)),
+            "none" => ("none", Arc::new(NoCheckDigit)),
+            "verhoeff" => ("verhoeff", Arc::new(Verhoeff)),
+            "damm" => ("damm", Arc::new(Damm)),
+            "mod97_10" | "mod97-10" => ("mod97_10", Arc::new(Mod97_10)),
+            _ => return None,
+        };
+        Some(Self { name: name.to_string(), algorithm: Some(algorithm) })
+    }
+
+    /// Luhn（模 10），网络未指定算法时使用
+    pub fn luhn() -> Self {
+        Self::builtin("luhn").expect("内置算法")
+    }
+
+    /// 定义文件中使用的名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 是否已绑定到实际算法
+    pub fn is_bound(&self) -> bool {
+        self.algorithm.is_some()
+    }
+
+    /// 对应的校验位算法；未绑定的名称计算校验位时返回错误、验证时总是失败
+    pub fn algorithm(&self) -> &dyn CheckDigitAlgorithm {
+        self.algorithm.as_deref().unwrap_or(&Unbound)
+    }
+}
+
+impl Default for CheckDigitScheme {
+    fn default() -> Self {
+        Self::luhn()
+    }
+}
+
+/// 按名称比较
+impl PartialEq for CheckDigitScheme {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CheckDigitScheme {}
+
+impl fmt::Debug for CheckDigitScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CheckDigitScheme").field(&self.name).finish()
+    }
+}
+
+impl Serialize for CheckDigitScheme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name)
+    }
+}
+
+impl<'de> Deserialize<'de> for CheckDigitScheme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(Self::builtin(&name).unwrap_or(Self { name, algorithm: None }))
+    }
+}
+
+/// 尚未绑定的自定义算法名称
+struct Unbound;
+
+impl CheckDigitAlgorithm for Unbound {
+    fn name(&self) -> &'static str {
+        "未注册"
+    }
+
+    fn compute(&self, _payload: &str) -> Result<String, String> {
+        Err("校验位算法尚未注册".to_string())
+    }
+}
+
+/// IIN 前缀区间，起止值位数相同，位数即前缀长度
+///
+/// 定义文件中写作字符串：`"4"`、`"34"`、`"2221-2720"`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct IinRange {
+    pub start: u64,
+    pub end: u64,
+    pub prefix_len: usize,
+}
+
+impl IinRange {
+    /// 创建区间，`prefix_len` 为前缀位数
+    pub fn new(start: u64, end: u64, prefix_len: usize) -> Result<Self, String> {
+        let range = Self { start, end, prefix_len };
+        if prefix_len == 0 || prefix_len > 12 {
+            return Err(format!("IIN 前缀长度必须在 1-12 之间: {}", range));
+        }
+        if start > end {
+            return Err(format!("IIN 区间起始值大于结束值: {}", range));
+        }
+        if end >= 10u64.pow(prefix_len as u32) {
+            return Err(format!("IIN 区间超出前缀长度: {}", range));
+        }
+        Ok(range)
+    }
+
+    /// 解析 `"51"` 或 `"2221-2720"` 形式的区间
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let (start, end) = text.split_once('-').unwrap_or((text, text));
+        let (start, end) = (start.trim(), end.trim());
+
+        let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+        if !is_digits(start) || !is_digits(end) || start.len() != end.len() {
+            return Err(format!("IIN 区间无效: {:?}", text));
+        }
+
+        let parse = |s: &str| s.parse::<u64>().map_err(|_| format!("IIN 区间无效: {:?}", text));
+        Self::new(parse(start)?, parse(end)?, start.len())
+    }
+
+    /// 判断卡号（或更长的 BIN）是否以该区间内的前缀开头
+    pub fn contains(&self, number: &str) -> bool {
+        number
+            .get(..self.prefix_len)
+            .filter(|prefix| prefix.chars().all(|c| c.is_ascii_digit()))
+            .and_then(|prefix| prefix.parse::<u64>().ok())
+            .is_some_and(|prefix| (self.start..=self.end).contains(&prefix))
+    }
+
+    /// 区间内前缀的数量
+    pub fn size(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// 格式化为固定位数的前缀
+    pub fn format_prefix(&self, prefix: u64) -> String {
+        format!("{:0width$}", prefix, width = self.prefix_len)
+    }
+
+    /// 在区间内随机选取一个前缀
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        self.format_prefix(rng.gen_range(self.start..=self.end))
+    }
+}
+
+impl fmt::Display for IinRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.format_prefix(self.start))
+        } else {
+            write!(f, "{}-{}", self.format_prefix(self.start), self.format_prefix(self.end))
+        }
+    }
+}
+
+impl TryFrom<String> for IinRange {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<IinRange> for String {
+    fn from(range: IinRange) -> Self {
+        range.to_string()
+    }
+}
+
+/// 卡网络定义：IIN 区间、允许的卡号长度与安全码规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardNetwork {
+    pub name: String,
+    pub identifier: String,
+    pub ranges: Vec<IinRange>,
+    pub length: Vec<usize>,
+    /// 安全码位数，0 表示该网络没有安全码
+    pub cvv_length: usize,
+    /// 安全码在该网络中的名称（CVV2、CVC2、CID、CVN2 等），未设置时为 `CVV`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cvv_label: Option<String>,
+    #[serde(default)]
+    pub check_digit: CheckDigitScheme,
+}
+
+impl CardNetwork {
+    fn new(name: &str, identifier: &str, ranges: &[&str], length: &[usize], cvv_length: usize, cvv_label: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            identifier: identifier.to_string(),
+            ranges: ranges
+                .iter()
+                .map(|r| IinRange::parse(r).expect("内置 IIN 区间无效"))
+                .collect(),
+            length: length.to_vec(),
+            cvv_length,
+            cvv_label: Some(cvv_label.to_string()),
+            check_digit: CheckDigitScheme::luhn(),
+        }
+    }
+
+    /// 判断 BIN（或卡号）是否落在该网络的任一 IIN 区间内
+    pub fn matches_bin(&self, bin: &str) -> bool {
+        self.ranges.iter().any(|range| range.contains(bin))
+    }
+
+    /// 判断卡号是否属于该网络：前缀与长度都必须匹配
+    pub fn matches(&self, card_number: &str) -> bool {
+        self.is_valid_length(card_number.len()) && self.matches_bin(card_number)
+    }
+
+    /// 安全码名称，用于导出与提示
+    pub fn cvv_label(&self) -> &str {
+        self.cvv_label.as_deref().unwrap_or("CVV")
+    }
+
+    /// 检查安全码是否符合该网络的规则，不符合时返回原因
+    pub fn check_cvv(&self, cvv: &str) -> Result<(), String> {
+        self.security_code_error(cvv).map_or(Ok(()), |error| Err(error.to_string()))
+    }
+
+    /// 安全码不符合该网络规则时返回对应的验证错误
+    pub fn security_code_error(&self, cvv: &str) -> Option<ValidationError> {
+        if self.cvv_length == 0 {
+            return Some(ValidationError::NoSecurityCode { network: self.name.clone() });
+        }
+        if cvv.len() != self.cvv_length || !cvv.chars().all(|c| c.is_ascii_digit()) {
+            return Some(ValidationError::InvalidSecurityCode {
+                label: self.cvv_label().to_string(),
+                length: self.cvv_length,
+            });
+        }
+        None
+    }
+
+    /// 判断卡号长度是否被该网络允许
+    pub fn is_valid_length(&self, length: usize) -> bool {
+        self.length.contains(&length)
+    }
+
+    /// 检查定义是否完整可用
+    pub fn validate(&self) -> Result<(), String> {
+        if self.identifier.is_empty() || self.name.is_empty() {
+            return Err("网络名称和标识不能为空".to_string());
+        }
+        if self.identifier == "random" {
+            return Err("`random` 是保留的网络标识".to_string());
+        }
+        if self.ranges.is_empty() {
+            return Err(format!("网络 {} 没有定义 IIN 区间", self.identifier));
+        }
+        if self.length.is_empty() || self.length.iter().any(|&l| !(8..=19).contains(&l)) {
+            return Err(format!("网络 {} 的卡号长度必须在 8-19 之间", self.identifier));
+        }
+        if !self.check_digit.is_bound() {
+            return Err(format!("网络 {} 使用了未注册的校验位算法: {}", self.identifier, self.check_digit.name()));
+        }
+        // 前缀后至少要留出校验位
+        let check_length = self.check_digit.algorithm().check_length();
+        if let Some(range) = self.ranges.iter().find(|r| self.length.iter().any(|&l| r.prefix_len + check_length > l)) {
+            return Err(format!("网络 {} 的 IIN 区间 {} 过长", self.identifier, range));
+        }
+        if self.cvv_length > 4 {
+            return Err(format!("网络 {} 的 CVV 长度不能超过 4", self.identifier));
+        }
+        Ok(())
+    }
+}
+
+/// 网络识别的候选项：匹配的网络及其命中的 IIN 区间
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkCandidate {
+    pub identifier: String,
+    pub name: String,
+    pub range: IinRange,
+}
+
+/// 网络定义文件的结构
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NetworkConfig {
+    #[serde(default)]
+    networks: Vec<CardNetwork>,
+}
+
+/// 卡网络注册表，按注册顺序保存网络定义与自定义校验位算法
+#[derive(Debug, Clone, PartialEq)]
+pub struct CardNetworkRegistry {
+    networks: Vec<CardNetwork>,
+    check_digits: Vec<CheckDigitScheme>,
+}
+
+impl CardNetworkRegistry {
+    /// 内置网络：Visa、Mastercard、American Express、Discover、UnionPay、Diners Club
+    pub fn builtin() -> Self {
+        let networks = vec![
+            CardNetwork::new("Visa", "visa", &["4"], &[13, 16, 19], 3, "CVV2"),
+            CardNetwork::new("Mastercard", "mastercard", &["51-55", "2221-2720"], &[16], 3, "CVC2"),
+            CardNetwork::new("American Express", "amex", &["34", "37"], &[15], 4, "CID"),
+            CardNetwork::new("Discover", "discover", &["6011", "622126-622925", "644-649", "65"], &[16], 3, "CID"),
+            CardNetwork::new("UnionPay", "unionpay", &["62"], &[16, 17, 18, 19], 3, "CVN2"),
+            CardNetwork::new("Diners Club", "diners", &["300-305", "36", "38"], &[14, 16], 3, "CVV2"),
+        ];
+
+        Self { networks, check_digits: Vec::new() }
+    }
+
+    /// 进程内共享的内置注册表，首次调用时构建，之后直接复用
+    pub fn shared_builtin() -> Arc<Self> {
+        Arc::clone(&SHARED_BUILTIN)
+    }
+
+    /// 不含任何网络的空注册表（内置校验位算法总是可用）
+    pub fn empty() -> Self {
+        Self { networks: Vec::new(), check_digits: Vec::new() }
+    }
+
+    /// 从 TOML 定义解析网络（`[[networks]]` 数组），不包含内置网络
+    pub fn from_toml_str(content: &str) -> Result<Self, String> {
+        let mut registry = Self::empty();
+        registry.load_toml_str(content)?;
+        Ok(registry)
+    }
+
+    /// 从 JSON 定义解析网络（`{"networks": [...]}`），不包含内置网络
+    pub fn from_json_str(content: &str) -> Result<Self, String> {
+        let mut registry = Self::empty();
+        registry.load_json_str(content)?;
+        Ok(registry)
+    }
+
+    /// 从定义文件解析网络，`.json` 按 JSON 解析，其余按 TOML 解析
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let mut registry = Self::empty();
+        registry.load_file(path)?;
+        Ok(registry)
+    }
+
+    /// 在内置网络基础上合并定义文件中的网络（同名标识会被覆盖）
+    pub fn builtin_with_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let mut registry = Self::builtin();
+        registry.load_file(path)?;
+        Ok(registry)
+    }
+
+    /// 把 TOML 定义中的网络注册到当前注册表，定义可引用已注册的自定义校验位算法
+    pub fn load_toml_str(&mut self, content: &str) -> Result<(), String> {
+        let config: NetworkConfig = toml::from_str(content).map_err(|e| format!("无法解析网络定义: {}", e))?;
+        self.load_config(config)
+    }
+
+    /// 把 JSON 定义中的网络注册到当前注册表，定义可引用已注册的自定义校验位算法
+    pub fn load_json_str(&mut self, content: &str) -> Result<(), String> {
+        let config: NetworkConfig =
+            serde_json::from_str(content).map_err(|e| format!("无法解析网络定义: {}", e))?;
+        self.load_config(config)
+    }
+
+    /// 把定义文件中的网络注册到当前注册表，`.json` 按 JSON 解析，其余按 TOML 解析
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_json {
+            self.load_json_str(&content)
+        } else {
+            self.load_toml_str(&content)
+        }
+    }
+
+    fn load_config(&mut self, config: NetworkConfig) -> Result<(), String> {
+        for network in config.networks {
+            self.register_network(network)?;
+        }
+        Ok(())
+    }
+
+    /// 注册自定义校验位算法，之后注册的网络可在定义中按名称引用；名称已存在时替换原算法，
+    /// 已注册的网络随之改用新算法。内置算法不能被替换
+    pub fn register_check_digit(&mut self, scheme: CheckDigitScheme) -> Result<(), String> {
+        if scheme.name.is_empty() || !scheme.is_bound() {
+            return Err("自定义校验位算法须有名称与实现".to_string());
+        }
+        if CheckDigitScheme::builtin(&scheme.name).is_some() {
+            return Err(format!("内置校验位算法 {} 不能被替换", scheme.name));
+        }
+
+        for network in self.networks.iter_mut().filter(|n| n.check_digit == scheme) {
+            network.check_digit = scheme.clone();
+        }
+        match self.check_digits.iter_mut().find(|s| **s == scheme) {
+            Some(existing) => *existing = scheme,
+            None => self.check_digits.push(scheme),
+        }
+        Ok(())
+    }
+
+    /// 已注册的自定义校验位算法
+    pub fn check_digits(&self) -> &[CheckDigitScheme] {
+        &self.check_digits
+    }
+
+    /// 注册网络；标识已存在时替换原定义。网络按名称引用的校验位算法在此绑定
+    pub fn register_network(&mut self, mut network: CardNetwork) -> Result<(), String> {
+        if !network.check_digit.is_bound() {
+            if let Some(scheme) = self.check_digits.iter().find(|s| **s == network.check_digit) {
+                network.check_digit = scheme.clone();
+            }
+        }
+        network.validate()?;
+
+        match self.networks.iter_mut().find(|n| n.identifier == network.identifier) {
+            Some(existing) => *existing = network,
+            None => self.networks.push(network),
+        }
+        Ok(())
+    }
+
+    /// 将另一个注册表中的自定义校验位算法与网络依次注册到当前注册表
+    pub fn merge(&mut self, other: CardNetworkRegistry) -> Result<(), String> {
+        for scheme in other.check_digits {
+            self.register_check_digit(scheme)?;
+        }
+        for network in other.networks {
+            self.register_network(network)?;
+        }
+        Ok(())
+    }
+
+    /// 按注册顺序返回全部网络
+    pub fn networks(&self) -> &[CardNetwork] {
+        &self.networks
+    }
+
+    /// 导出为 TOML 定义
+    pub fn to_toml_string(&self) -> Result<String, String> {
+        let config = NetworkConfig { networks: self.networks.clone() };
+        toml::to_string(&config).map_err(|e| e.to_string())
+    }
+
+    /// 识别卡号所属网络名称
+    pub fn detect_network(&self, card_number: &str) -> Option<String> {
+        self.detect(card_number).map(|network| network.name.clone())
+    }
+
+    /// 识别卡号所属网络，多个网络匹配时取最具体（前缀最长、区间最窄）的一个
+    pub fn detect(&self, card_number: &str) -> Option<&CardNetwork> {
+        self.ranked_matches(card_number, true).first().map(|(network, _)| *network)
+    }
+
+    /// 只按前缀识别最匹配的网络，不检查长度；用于在长度不对时提示应属的网络
+    pub fn detect_by_prefix(&self, card_number: &str) -> Option<&CardNetwork> {
+        self.ranked_matches(card_number, false).first().map(|(network, _)| *network)
+    }
+
+    /// 按具体程度列出所有匹配的网络，首项即 [`Self::detect`] 的结果
+    ///
+    /// 返回多个候选项说明卡号落在重叠区间内（如 UnionPay 62 与 Discover 622126-622925），
+    /// 常见于联名卡。
+    pub fn detect_candidates(&self, card_number: &str) -> Vec<NetworkCandidate> {
+        self.ranked_matches(card_number, true)
+            .into_iter()
+            .map(|(network, range)| NetworkCandidate {
+                identifier: network.identifier.clone(),
+                name: network.name.clone(),
+                range: *range,
+            })
+            .collect()
+    }
+
+    /// 每个匹配网络取其最具体的区间，再按前缀长度降序、区间大小升序、注册顺序排序；
+    /// `check_length` 为假时不要求长度匹配
+    fn ranked_matches(&self, card_number: &str, check_length: bool) -> Vec<(&CardNetwork, &IinRange)> {
+        let specificity = |range: &IinRange| (std::cmp::Reverse(range.prefix_len), range.size());
+
+        let mut matches: Vec<(usize, &CardNetwork, &IinRange)> = self
+            .networks
+            .iter()
+            .enumerate()
+            .filter(|(_, network)| !check_length || network.is_valid_length(card_number.len()))
+            .filter_map(|(order, network)| {
+                network
+                    .ranges
+                    .iter()
+                    .filter(|range| range.contains(card_number))
+                    .min_by_key(|range| specificity(range))
+                    .map(|range| (order, network, range))
+            })
+            .collect();
+
+        matches.sort_by_key(|&(order, _, range)| (specificity(range), order));
+        matches.into_iter().map(|(_, network, range)| (network, range)).collect()
+    }
+
+    /// 按标识查找网络
+    pub fn get_network_by_identifier(&self, identifier: &str) -> Option<&CardNetwork> {
+        self.networks.iter().find(|n| n.identifier == identifier)
+    }
+
+    /// 网络标识与名称列表，首项为 `random`
+    pub fn get_network_names(&self) -> Vec<(String, String)> {
+        std::iter::once(("random".to_string(), "Random".to_string()))
+            .chain(self.networks.iter().map(|n| (n.identifier.clone(), n.name.clone())))
+            .collect()
+    }
+}
+
+impl Default for CardNetworkRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check_digit::tests::DigitSum;
+
+    #[test]
+    fn test_detect_visa() {
+        let registry = CardNetworkRegistry::builtin();
+        assert_eq!(registry.detect_network("4111111111111111"), Some("Visa".to_string()));
+        assert_eq!(registry.detect_network("4111111111111"), Some("Visa".to_string()));
+        assert_eq!(registry.detect_network("4111111111111111111"), Some("Visa".to_string()));
+    }
+
+    #[test]
+    fn test_detect_mastercard() {
+        let registry = CardNetworkRegistry::builtin();
+        assert_eq!(registry.detect_network("5555555555554444"), Some("Mastercard".to_string()));
+        assert_eq!(registry.detect_network("2221000000000000"), Some("Mastercard".to_string()));
+    }
+
+    #[test]
+    fn test_detect_amex() {
+        let registry = CardNetworkRegistry::builtin();
+        assert_eq!(registry.detect_network("378282246310005"), Some("American Express".to_string()));
+        assert_eq!(registry.detect_network("371449635398431"), Some("American Express".to_string()));
+    }
+
+    #[test]
+    fn test_detect_invalid() {
+        assert_eq!(CardNetworkRegistry::builtin().detect_network("1234567890123456"), None);
+    }
+
+    #[test]
+    fn test_load_from_toml() {
+        let registry = CardNetworkRegistry::from_toml_str(
+            r#"
+            [[networks]]
+            name = "JCB"
+            identifier = "jcb"
+            ranges = ["3528-3589"]
+            length = [16, 19]
+            cvv_length = 3
+            check_digit = "luhn"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(registry.networks().len(), 1);
+        assert_eq!(registry.detect_network("3528000000000007"), Some("JCB".to_string()));
+        assert_eq!(registry.networks()[0].cvv_label(), "CVV");
+    }
+
+    #[test]
+    fn test_security_code_rules() {
+        let registry = CardNetworkRegistry::builtin();
+        let amex = registry.get_network_by_identifier("amex").unwrap();
+        assert_eq!((amex.cvv_length, amex.cvv_label()), (4, "CID"));
+        assert!(amex.check_cvv("1234").is_ok());
+        assert_eq!(amex.check_cvv("123").unwrap_err(), "CID 应为 4 位数字");
+
+        let mastercard = registry.get_network_by_identifier("mastercard").unwrap();
+        assert_eq!(mastercard.cvv_label(), "CVC2");
+        assert!(mastercard.check_cvv("12a").is_err());
+
+        let mut gift = mastercard.clone();
+        gift.cvv_length = 0;
+        assert!(gift.check_cvv("123").is_err());
+    }
+
+    #[test]
+    fn test_register_network_replaces_existing() {
+        let mut registry = CardNetworkRegistry::builtin();
+        let mut visa = registry.get_network_by_identifier("visa").unwrap().clone();
+        visa.length = vec![16];
+        registry.register_network(visa).unwrap();
+
+        assert_eq!(registry.networks().len(), 6);
+        assert_eq!(registry.detect_network("4111111111111"), None);
+    }
+
+    #[test]
+    fn test_register_network_rejects_invalid() {
+        let mut registry = CardNetworkRegistry::builtin();
+        let mut network = registry.get_network_by_identifier("visa").unwrap().clone();
+        network.identifier = "random".to_string();
+        assert!(registry.register_network(network.clone()).is_err());
+
+        network.identifier = "bad".to_string();
+        network.ranges = vec![IinRange::new(4, 4, 1).unwrap()];
+        network.length = vec![1];
+        assert!(registry.register_network(network).is_err());
+    }
+
+    #[test]
+    fn test_example_config_loads() {
+        let example = CardNetworkRegistry::from_toml_str(include_str!("../../../config/networks.example.toml")).unwrap();
+        let mut registry = CardNetworkRegistry::builtin();
+        registry.merge(example).unwrap();
+
+        let ids: Vec<_> = registry.networks().iter().map(|n| n.identifier.as_str()).collect();
+        for id in ["jcb", "maestro", "rupay", "mir", "elo", "verve", "troy", "uatp", "gift", "loyalty"] {
+            assert!(ids.contains(&id), "missing {}", id);
+        }
+        let gift = registry.get_network_by_identifier("gift").unwrap();
+        assert_eq!(gift.check_digit, CheckDigitScheme::builtin("damm").unwrap());
+        assert_eq!(gift.check_digit.algorithm().name(), "Damm");
+        let mod97 = serde_json::from_str::<CheckDigitScheme>("\"mod97-10\"").unwrap();
+        assert_eq!((mod97.name(), mod97.algorithm().check_length()), ("mod97_10", 2));
+        assert_eq!(registry.detect_network("2200000000000004"), Some("Mir".to_string()));
+    }
+
+    #[test]
+    fn test_custom_check_digit_algorithm() {
+        let definition = r#"
+            [[networks]]
+            name = "Fleet"
+            identifier = "fleet"
+            ranges = ["7088"]
+            length = [16]
+            cvv_length = 0
+            check_digit = "digit_sum"
+        "#;
+
+        // 未注册的名称无法使用
+        let err = CardNetworkRegistry::from_toml_str(definition).unwrap_err();
+        assert!(err.contains("未注册的校验位算法: digit_sum"), "{}", err);
+
+        let mut registry = CardNetworkRegistry::builtin();
+        registry.register_check_digit(CheckDigitScheme::new("digit_sum", DigitSum)).unwrap();
+        registry.load_toml_str(definition).unwrap();
+        let fleet = registry.get_network_by_identifier("fleet").unwrap();
+        assert_eq!(fleet.check_digit.name(), "digit_sum");
+        assert_eq!(fleet.check_digit.algorithm().compute("708812345678901").unwrap(), "9");
+        assert!(fleet.check_digit.algorithm().is_valid("7088123456789019"));
+
+        // 导出后引用名称，加载到注册了同名算法的注册表时重新绑定
+        let toml = registry.to_toml_string().unwrap();
+        assert!(toml.contains("check_digit = \"digit_sum\""));
+        let mut other = CardNetworkRegistry::empty();
+        other.register_check_digit(CheckDigitScheme::new("digit_sum", DigitSum)).unwrap();
+        other.load_toml_str(&toml).unwrap();
+        assert_eq!(other.get_network_by_identifier("fleet").unwrap().check_digit.algorithm().name(), "Digit Sum");
+
+        assert!(registry.register_check_digit(CheckDigitScheme::new("luhn", DigitSum)).is_err());
+        let unbound: CheckDigitScheme = serde_json::from_str("\"digit_sum\"").unwrap();
+        assert!(!unbound.is_bound());
+        assert!(unbound.algorithm().compute("1234").is_err());
+        assert!(!unbound.algorithm().is_valid("12340"));
+    }
+
+    #[test]
+    fn test_validate_leaves_room_for_check_digits() {
+        let mut network = CardNetworkRegistry::builtin().get_network_by_identifier("visa").unwrap().clone();
+        network.ranges = vec![IinRange::parse("123456789012").unwrap()];
+        network.length = vec![13];
+        assert!(network.validate().is_ok());
+
+        network.check_digit = CheckDigitScheme::builtin("mod97_10").unwrap();
+        assert!(network.validate().unwrap_err().contains("过长"));
+        network.check_digit = CheckDigitScheme::builtin("none").unwrap();
+        network.length = vec![12];
+        assert!(network.validate().is_ok());
+    }
+
+    #[test]
+    fn test_builtin_roundtrips_through_toml() {
+        let registry = CardNetworkRegistry::builtin();
+        let toml = registry.to_toml_string().unwrap();
+        assert_eq!(CardNetworkRegistry::from_toml_str(&toml).unwrap(), registry);
+    }
+
+    #[test]
+    fn test_parse_iin_range() {
+        assert_eq!(IinRange::parse("2221-2720").unwrap(), IinRange::new(2221, 2720, 4).unwrap());
+        assert_eq!(IinRange::parse("4").unwrap().to_string(), "4");
+        assert_eq!(IinRange::parse("0300-0305").unwrap().to_string(), "0300-0305");
+        assert!(IinRange::parse("55-51").is_err());
+        assert!(IinRange::parse("51-5").is_err());
+        assert!(IinRange::parse("4a").is_err());
+        assert!(IinRange::parse("").is_err());
+    }
+
+    #[test]
+    fn test_iin_range_contains() {
+        let range = IinRange::parse("2221-2720").unwrap();
+        assert!(range.contains("2221000000000000"));
+        assert!(range.contains("2720"));
+        assert!(!range.contains("2220999999999999"));
+        assert!(!range.contains("2721000000000000"));
+        assert!(!range.contains("222"));
+    }
+
+    #[test]
+    fn test_detect_discover_and_diners_ranges() {
+        let registry = CardNetworkRegistry::builtin();
+        assert_eq!(registry.detect_network("6445644564456445"), Some("Discover".to_string()));
+        assert_eq!(registry.detect_network("30569309025904"), Some("Diners Club".to_string()));
+        assert_eq!(registry.detect_network("30669309025904"), None);
+    }
+
+    #[test]
+    fn test_detect_prefers_most_specific_range() {
+        let registry = CardNetworkRegistry::builtin();
+
+        // 16 位：Discover 622126-622925 比 UnionPay 62 更具体
+        assert_eq!(registry.detect_network("6221260000000000"), Some("Discover".to_string()));
+        let candidates = registry.detect_candidates("6221260000000000");
+        let names: Vec<_> = candidates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Discover", "UnionPay"]);
+        assert_eq!(candidates[0].range.to_string(), "622126-622925");
+
+        // 19 位只有 UnionPay 允许
+        assert_eq!(registry.detect_network("6221260000000000000"), Some("UnionPay".to_string()));
+        assert_eq!(registry.detect_candidates("6221260000000000000").len(), 1);
+        assert_eq!(registry.detect_network("6200000000000000"), Some("UnionPay".to_string()));
+    }
+
+    #[test]
+    fn test_detect_is_independent_of_registration_order() {
+        let mut elo_first = CardNetworkRegistry::from_toml_str(
+            r#"
+            [[networks]]
+            name = "Elo"
+            identifier = "elo"
+            ranges = ["401178-401179"]
+            length = [16]
+            cvv_length = 3
+            "#,
+        )
+        .unwrap();
+        elo_first.merge(CardNetworkRegistry::builtin()).unwrap();
+
+        let mut visa_first = CardNetworkRegistry::builtin();
+        visa_first.merge(elo_first.clone()).unwrap();
+
+        for registry in [elo_first, visa_first] {
+            assert_eq!(registry.detect_network("4011780000000000"), Some("Elo".to_string()));
+            assert_eq!(registry.detect_network("4111111111111111"), Some("Visa".to_string()));
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! BER-TLV 编码与解析（EMV 使用的子集：1-3 字节标签、短格式与长格式长度、嵌套结构）。
+
+/// 一个 TLV 数据对象，构造型标签（如 `70`）的值是嵌套的 TLV
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tlv {
+    /// 标签，按字节大端存放（如 `0x5F24`）
+    pub tag: u32,
+    pub value: Vec<u8>,
+}
+
+impl Tlv {
+    pub fn new(tag: u32, value: impl Into<Vec<u8>>) -> Self {
+        Self { tag, value: value.into() }
+    }
+
+    /// 由子对象组成的构造型 TLV
+    pub fn constructed(tag: u32, children: &[Tlv]) -> Self {
+        Self::new(tag, encode_all(children))
+    }
+
+    /// 标签字节（去掉前导零）
+    pub fn tag_bytes(&self) -> Vec<u8> {
+        let bytes = self.tag.to_be_bytes();
+        let start = bytes.iter().position(|&b| b != 0).unwrap_or(3);
+        bytes[start..].to_vec()
+    }
+
+    /// 标签的十六进制表示，如 `5F24`
+    pub fn tag_hex(&self) -> String {
+        to_hex(&self.tag_bytes())
+    }
+
+    /// 首字节第 6 位为 1 的标签是构造型
+    pub fn is_constructed(&self) -> bool {
+        self.tag_bytes()[0] & 0x20 != 0
+    }
+
+    /// 解析构造型标签的子对象，原始型标签返回空列表
+    pub fn children(&self) -> Result<Vec<Tlv>, String> {
+        if self.is_constructed() {
+            parse(&self.value)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = self.tag_bytes();
+        let len = self.value.len();
+        if len < 0x80 {
+            bytes.push(len as u8);
+        } else {
+            let len_bytes = (len as u32).to_be_bytes();
+            let start = len_bytes.iter().position(|&b| b != 0).unwrap_or(3);
+            bytes.push(0x80 | (4 - start) as u8);
+            bytes.extend_from_slice(&len_bytes[start..]);
+        }
+        bytes.extend_from_slice(&self.value);
+        bytes
+    }
+}
+
+/// 依次编码多个 TLV
+pub fn encode_all(tlvs: &[Tlv]) -> Vec<u8> {
+    tlvs.iter().flat_map(Tlv::encode).collect()
+}
+
+/// 解析一串 TLV，跳过对象之间的 `00` / `FF` 填充字节
+pub fn parse(bytes: &[u8]) -> Result<Vec<Tlv>, String> {
+    let mut tlvs = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        if bytes[pos] == 0x00 || bytes[pos] == 0xFF {
+            pos += 1;
+            continue;
+        }
+
+        // 标签：首字节低 5 位全为 1 时后续字节最高位为 1 表示还有下一字节
+        let start = pos;
+        let mut tag = bytes[pos] as u32;
+        pos += 1;
+        if bytes[start] & 0x1F == 0x1F {
+            loop {
+                let b = *bytes.get(pos).ok_or_else(|| format!("第 {} 字节处的标签不完整", start))?;
+                tag = (tag << 8) | b as u32;
+                pos += 1;
+                if b & 0x80 == 0 {
+                    break;
+                }
+                if pos - start >= 3 {
+                    return Err(format!("第 {} 字节处的标签超过 3 字节", start));
+                }
+            }
+        }
+
+        // 长度：短格式一个字节，长格式 0x81-0x84 后跟 1-4 字节
+        let first = *bytes.get(pos).ok_or_else(|| format!("标签 {:X} 缺少长度", tag))?;
+        pos += 1;
+        let len = if first & 0x80 == 0 {
+            first as usize
+        } else {
+            let count = (first & 0x7F) as usize;
+            if !(1..=4).contains(&count) || pos + count > bytes.len() {
+                return Err(format!("标签 {:X} 的长度字段无效", tag));
+            }
+            let len = bytes[pos..pos + count].iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+            pos += count;
+            len
+        };
+
+        let value = bytes
+            .get(pos..pos + len)
+            .ok_or_else(|| format!("标签 {:X} 的值超出数据末尾（需要 {} 字节）", tag, len))?;
+        pos += len;
+        tlvs.push(Tlv::new(tag, value));
+    }
+
+    Ok(tlvs)
+}
+
+/// 解析十六进制文本，忽略空白
+pub fn parse_hex(hex: &str) -> Result<Vec<Tlv>, String> {
+    parse(&from_hex(hex)?)
+}
+
+/// 在一组 TLV 及其嵌套结构中按深度优先查找标签
+pub fn find(tlvs: &[Tlv], tag: u32) -> Option<Tlv> {
+    tlvs.iter().find_map(|tlv| {
+        if tlv.tag == tag {
+            Some(tlv.clone())
+        } else {
+            tlv.children().ok().and_then(|children| find(&children, tag))
+        }
+    })
+}
+
+/// 字节转大写十六进制
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// 十六进制转字节，忽略空白
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("十六进制数据无效: {}", hex));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_and_parse() {
+        let record = Tlv::constructed(0x70, &[
+            Tlv::new(0x5A, from_hex("4111111111111111").unwrap()),
+            Tlv::new(0x5F24, from_hex("291231").unwrap()),
+            Tlv::new(0x9F07, from_hex("FF00").unwrap()),
+        ]);
+        let hex = to_hex(&record.encode());
+        assert_eq!(hex, "70155A0841111111111111115F24032912319F0702FF00");
+
+        let parsed = parse_hex(&hex).unwrap();
+        assert_eq!(parsed, vec![record]);
+        assert!(parsed[0].is_constructed());
+        assert_eq!(parsed[0].children().unwrap().len(), 3);
+        assert_eq!(find(&parsed, 0x5F24).unwrap().value, vec![0x29, 0x12, 0x31]);
+        assert!(find(&parsed, 0x57).is_none());
+    }
+
+    #[test]
+    fn test_long_lengths_and_padding() {
+        let tlv = Tlv::new(0x9F4B, vec![0xAB; 200]);
+        let encoded = tlv.encode();
+        assert_eq!(&encoded[..4], &[0x9F, 0x4B, 0x81, 200]);
+
+        let mut padded = vec![0x00, 0xFF];
+        padded.extend_from_slice(&encoded);
+        assert_eq!(parse(&padded).unwrap(), vec![tlv]);
+    }
+
+    #[test]
+    fn test_rejects_malformed_data() {
+        assert!(parse_hex("5A08411111").is_err());
+        assert!(parse_hex("5F").is_err());
+        assert!(parse_hex("5A").is_err());
+        assert!(parse_hex("5A0").is_err());
+    }
+}
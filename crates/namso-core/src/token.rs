@@ -0,0 +1,182 @@
+//! 网络令牌（Network Token）测试数据：令牌卡号、令牌有效期、令牌请求方 ID，
+//! 以及可持久化的令牌库（令牌 → 资金卡号映射）。
+
+use crate::expiry::ExpiryPolicy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// 默认令牌请求方 ID（11 位数字，仅用于测试）
+pub const DEFAULT_TOKEN_REQUESTOR_ID: &str = "40000000001";
+
+/// 默认令牌有效期：36 个月后到期
+pub const DEFAULT_TOKEN_EXPIRY: ExpiryPolicy = ExpiryPolicy::FutureRange { min_months: 36, max_months: 36 };
+
+/// 令牌化参数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenOptions {
+    /// 令牌请求方 ID（11 位数字），默认为 [`DEFAULT_TOKEN_REQUESTOR_ID`]
+    pub requestor_id: Option<String>,
+    /// 令牌 BIN 或 BIN 模式（见 [`crate::bin_pattern`]），默认从卡所属网络的 IIN 区间中选择
+    pub token_bin: Option<String>,
+    /// 令牌有效期策略，默认为 [`DEFAULT_TOKEN_EXPIRY`]
+    pub expiry: Option<ExpiryPolicy>,
+}
+
+impl TokenOptions {
+    /// 实际使用的令牌请求方 ID，格式无效时返回错误
+    pub fn requestor_id(&self) -> Result<&str, String> {
+        let id = self.requestor_id.as_deref().unwrap_or(DEFAULT_TOKEN_REQUESTOR_ID);
+        if id.len() != 11 || !id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("令牌请求方 ID 应为 11 位数字: {}", id));
+        }
+        Ok(id)
+    }
+}
+
+/// 一个令牌及其映射的资金卡
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenRecord {
+    /// 令牌卡号（与资金卡号长度相同、BIN 不同，校验位按网络规则计算）
+    pub token: String,
+    pub token_exp_month: String,
+    pub token_exp_year: String,
+    /// 令牌有效期（MM/YY）
+    pub token_expiry: String,
+    pub requestor_id: String,
+    /// 资金卡号
+    pub pan: String,
+    /// 资金卡有效期（MM/YY）
+    pub pan_expiry: String,
+    pub network: String,
+    /// 令牌创建日期（YYYY-MM-DD）
+    pub created: String,
+}
+
+/// 令牌库，可持久化为 JSON 文件，跨多次运行保持映射
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenVault {
+    tokens: BTreeMap<String, TokenRecord>,
+}
+
+impl TokenVault {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从 JSON 文件加载，文件不存在时返回空令牌库
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        let records: Vec<TokenRecord> =
+            serde_json::from_str(&content).map_err(|e| format!("{}: 令牌库格式无效: {}", path.display(), e))?;
+        Ok(Self {
+            tokens: records.into_iter().map(|record| (record.token.clone(), record)).collect(),
+        })
+    }
+
+    /// 保存为 JSON 文件（按令牌排序，便于比对）
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let records: Vec<&TokenRecord> = self.tokens.values().collect();
+        let content = serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?;
+        fs::write(path, content + "\n").map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, token: &str) -> Option<&TokenRecord> {
+        self.tokens.get(token)
+    }
+
+    /// 由令牌查找资金卡，令牌不存在时返回错误
+    pub fn detokenize(&self, token: &str) -> Result<&TokenRecord, String> {
+        self.get(token.trim()).ok_or_else(|| format!("令牌不存在: {}", token.trim()))
+    }
+
+    /// 查找同一资金卡号与请求方已有的令牌
+    pub fn find(&self, pan: &str, requestor_id: &str) -> Option<&TokenRecord> {
+        self.tokens
+            .values()
+            .find(|record| record.pan == pan && record.requestor_id == requestor_id)
+    }
+
+    pub fn contains(&self, token: &str) -> bool {
+        self.tokens.contains_key(token)
+    }
+
+    /// 记录令牌，令牌已存在时返回 `false` 且不覆盖
+    pub fn insert(&mut self, record: TokenRecord) -> bool {
+        if self.contains(&record.token) {
+            return false;
+        }
+        self.tokens.insert(record.token.clone(), record);
+        true
+    }
+
+    /// 按令牌排序的全部记录
+    pub fn records(&self) -> impl Iterator<Item = &TokenRecord> {
+        self.tokens.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(token: &str, pan: &str) -> TokenRecord {
+        TokenRecord {
+            token: token.to_string(),
+            token_exp_month: "10".to_string(),
+            token_exp_year: "2029".to_string(),
+            token_expiry: "10/29".to_string(),
+            requestor_id: DEFAULT_TOKEN_REQUESTOR_ID.to_string(),
+            pan: pan.to_string(),
+            pan_expiry: "12/28".to_string(),
+            network: "Visa".to_string(),
+            created: "2026-10-18".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_vault_roundtrips_through_file() {
+        let path = std::env::temp_dir().join(format!("namso-tokens-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        assert!(TokenVault::load(&path).unwrap().is_empty());
+
+        let mut vault = TokenVault::new();
+        assert!(vault.insert(record("4895370000000006", "4111111111111111")));
+        assert!(!vault.insert(record("4895370000000006", "4012888888881881")));
+        vault.save(&path).unwrap();
+
+        let loaded = TokenVault::load(&path).unwrap();
+        assert_eq!(loaded, vault);
+        assert_eq!(loaded.detokenize(" 4895370000000006 ").unwrap().pan, "4111111111111111");
+        assert!(loaded.detokenize("4000000000000002").is_err());
+        assert!(loaded.find("4111111111111111", DEFAULT_TOKEN_REQUESTOR_ID).is_some());
+        assert!(loaded.find("4111111111111111", "40000000002").is_none());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_requestor_id() {
+        assert_eq!(TokenOptions::default().requestor_id().unwrap(), DEFAULT_TOKEN_REQUESTOR_ID);
+        let options = TokenOptions { requestor_id: Some("1234".to_string()), ..Default::default() };
+        assert!(options.requestor_id().is_err());
+    }
+}
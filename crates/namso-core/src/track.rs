@@ -0,0 +1,227 @@
+//! ISO 7813 磁道数据：Track 1（格式 B）与 Track 2，含纵向冗余校验字符（LRC）。
+
+/// 默认服务码：国际卡、带芯片、正常授权、无限制
+pub const DEFAULT_SERVICE_CODE: &str = "201";
+
+/// Track 1 含起止符与 LRC 的最大长度
+const TRACK1_MAX_LEN: usize = 79;
+
+/// Track 2 含起止符与 LRC 的最大长度
+const TRACK2_MAX_LEN: usize = 40;
+
+/// 一张卡的磁道数据
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackData {
+    pub cardholder: String,
+    pub service_code: String,
+    pub track1: String,
+    pub track2: String,
+}
+
+impl TrackData {
+    /// 用同一组字段生成 Track 1 与 Track 2
+    pub fn new(pan: &str, name: &str, expiry: &str, service_code: &str, discretionary: &str) -> Result<Self, String> {
+        Ok(Self {
+            cardholder: name.to_string(),
+            service_code: service_code.to_string(),
+            track1: track1(pan, name, expiry, service_code, discretionary)?,
+            track2: track2(pan, expiry, service_code, discretionary)?,
+        })
+    }
+}
+
+/// 生成 Track 1：`%B卡号^姓名^YYMM服务码自定义数据?LRC`
+///
+/// 姓名使用 `姓/名` 形式，只允许大写字母、空格与 `/.-'`，长度 2-26。
+pub fn track1(pan: &str, name: &str, expiry: &str, service_code: &str, discretionary: &str) -> Result<String, String> {
+    check_fields(pan, expiry, service_code, discretionary)?;
+    if !(2..=26).contains(&name.len())
+        || !name.chars().all(|c| c.is_ascii_uppercase() || " /.-'".contains(c))
+    {
+        return Err(format!("持卡人姓名无效（应为 2-26 位大写字母，如 ZHANG/WEI）: {}", name));
+    }
+
+    let data = format!("%B{}^{}^{}{}{}?", pan, name, expiry, service_code, discretionary);
+    if data.len() + 1 > TRACK1_MAX_LEN {
+        return Err(format!("Track 1 超过 {} 个字符", TRACK1_MAX_LEN));
+    }
+    let lrc = lrc(&data, 0x20, 0x3F);
+    Ok(format!("{}{}", data, lrc))
+}
+
+/// 生成 Track 2：`;卡号=YYMM服务码自定义数据?LRC`
+pub fn track2(pan: &str, expiry: &str, service_code: &str, discretionary: &str) -> Result<String, String> {
+    check_fields(pan, expiry, service_code, discretionary)?;
+
+    let data = format!(";{}={}{}{}?", pan, expiry, service_code, discretionary);
+    if data.len() + 1 > TRACK2_MAX_LEN {
+        return Err(format!("Track 2 超过 {} 个字符", TRACK2_MAX_LEN));
+    }
+    let lrc = lrc(&data, 0x30, 0x0F);
+    Ok(format!("{}{}", data, lrc))
+}
+
+/// 从磁道中解析出的字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedTrack {
+    pub pan: String,
+    /// 持卡人姓名，仅 Track 1 有
+    pub name: Option<String>,
+    /// 有效期（YYMM）
+    pub expiry: String,
+    pub service_code: String,
+    /// LRC 是否正确，磁道末尾没有 LRC 时为空
+    pub lrc_valid: Option<bool>,
+}
+
+/// 解析 Track 1（格式 B），末尾的 LRC 可省略
+pub fn parse_track1(track: &str) -> Result<ParsedTrack, String> {
+    let track = track.trim();
+    let (data, lrc_valid) = split_sentinels(track, "%B")?;
+    let mut fields = data.splitn(3, '^');
+    let (Some(pan), Some(name), Some(rest)) = (fields.next(), fields.next(), fields.next()) else {
+        return Err("Track 1 应为 %B卡号^姓名^YYMM服务码…? 格式".to_string());
+    };
+    let (expiry, service_code) = expiry_and_service_code(rest)?;
+    Ok(ParsedTrack {
+        pan: pan.to_string(),
+        name: Some(name.trim().to_string()),
+        expiry,
+        service_code,
+        lrc_valid,
+    })
+}
+
+/// 解析 Track 2，末尾的 LRC 可省略
+pub fn parse_track2(track: &str) -> Result<ParsedTrack, String> {
+    let track = track.trim();
+    let (data, lrc_valid) = split_sentinels(track, ";")?;
+    let Some((pan, rest)) = data.split_once('=') else {
+        return Err("Track 2 应为 ;卡号=YYMM服务码…? 格式".to_string());
+    };
+    let (expiry, service_code) = expiry_and_service_code(rest)?;
+    Ok(ParsedTrack {
+        pan: pan.to_string(),
+        name: None,
+        expiry,
+        service_code,
+        lrc_valid,
+    })
+}
+
+/// 去掉起始符与结束符，结束符后有 LRC 时一并检查
+fn split_sentinels<'a>(track: &'a str, start: &str) -> Result<(&'a str, Option<bool>), String> {
+    let body = track
+        .strip_prefix(start)
+        .ok_or_else(|| format!("磁道应以 {} 开头", start))?;
+    let (data, after) = body.split_once('?').ok_or_else(|| "磁道缺少结束符 ?".to_string())?;
+    let lrc_valid = match after.chars().count() {
+        0 => None,
+        1 => Some(is_lrc_valid(track)),
+        _ => return Err("结束符之后只能有一个 LRC 字符".to_string()),
+    };
+    Ok((data, lrc_valid))
+}
+
+fn expiry_and_service_code(rest: &str) -> Result<(String, String), String> {
+    match (rest.get(..4), rest.get(4..7)) {
+        (Some(expiry), Some(service_code)) if is_digits(expiry) && is_digits(service_code) => {
+            Ok((expiry.to_string(), service_code.to_string()))
+        }
+        _ => Err("有效期（YYMM）或服务码缺失".to_string()),
+    }
+}
+
+/// 检查磁道末尾的 LRC 是否正确，根据起始符区分 Track 1（`%`）与 Track 2（`;`）
+pub fn is_lrc_valid(track: &str) -> bool {
+    let Some(last) = track.chars().last() else {
+        return false;
+    };
+    let data = &track[..track.len() - last.len_utf8()];
+    match track.chars().next() {
+        Some('%') => lrc(data, 0x20, 0x3F) == last,
+        Some(';') => lrc(data, 0x30, 0x0F) == last,
+        _ => false,
+    }
+}
+
+/// 从起始符到结束符逐字符异或（Track 1 为 6 位字符集，Track 2 为 4 位字符集）
+fn lrc(data: &str, offset: u8, mask: u8) -> char {
+    let value = data.bytes().fold(0, |acc, b| acc ^ (b.wrapping_sub(offset) & mask));
+    (value + offset) as char
+}
+
+fn check_fields(pan: &str, expiry: &str, service_code: &str, discretionary: &str) -> Result<(), String> {
+    if !(12..=19).contains(&pan.len()) || !is_digits(pan) {
+        return Err(format!("卡号无效: {}", pan));
+    }
+    if expiry.len() != 4 || !is_digits(expiry) {
+        return Err(format!("有效期必须是 YYMM 格式: {}", expiry));
+    }
+    if service_code.len() != 3 || !is_digits(service_code) {
+        return Err(format!("服务码必须是 3 位数字: {}", service_code));
+    }
+    if !is_digits(discretionary) {
+        return Err(format!("自定义数据只能包含数字: {}", discretionary));
+    }
+    Ok(())
+}
+
+fn is_digits(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracks() {
+        let track1 = track1("4111111111111111", "ZHANG/WEI", "2812", "201", "10000123").unwrap();
+        assert!(track1.starts_with("%B4111111111111111^ZHANG/WEI^2812201"));
+        assert!(is_lrc_valid(&track1));
+
+        let track2 = track2("4111111111111111", "2812", "201", "10000123").unwrap();
+        assert!(track2.starts_with(";4111111111111111=2812201"));
+        assert!(track2.len() <= TRACK2_MAX_LEN);
+        assert!(is_lrc_valid(&track2));
+
+        // 改动任一字符都会使 LRC 失效
+        assert!(!is_lrc_valid(&track2.replacen("2812", "2813", 1)));
+        assert!(!is_lrc_valid(""));
+    }
+
+    #[test]
+    fn test_lrc_known_value() {
+        // 每个字符的 4 位值：; = B，1 = 1，= = D，? = F，异或结果为 8
+        assert_eq!(lrc(";1=?", 0x30, 0x0F), '8');
+    }
+
+    #[test]
+    fn test_parse_tracks() {
+        let track1 = track1("4111111111111111", "ZHANG/WEI", "2812", "201", "10000123").unwrap();
+        let parsed = parse_track1(&track1).unwrap();
+        assert_eq!(parsed.pan, "4111111111111111");
+        assert_eq!(parsed.name.as_deref(), Some("ZHANG/WEI"));
+        assert_eq!((parsed.expiry.as_str(), parsed.service_code.as_str()), ("2812", "201"));
+        assert_eq!(parsed.lrc_valid, Some(true));
+
+        let parsed = parse_track2(";4111111111111111=2812201?").unwrap();
+        assert_eq!(parsed.pan, "4111111111111111");
+        assert_eq!(parsed.lrc_valid, None);
+
+        let track2 = track2("4111111111111111", "2812", "201", "").unwrap();
+        assert_eq!(parse_track2(&track2.replacen("2812", "2813", 1)).unwrap().lrc_valid, Some(false));
+        assert!(parse_track2("4111111111111111=2812201?").is_err());
+        assert!(parse_track2(";4111111111111111=28?").is_err());
+        assert!(parse_track1("%B4111111111111111^ZHANG/WEI?").is_err());
+    }
+
+    #[test]
+    fn test_rejects_invalid_fields() {
+        assert!(track1("4111111111111111", "zhang/wei", "2812", "201", "").is_err());
+        assert!(track1("4111111111111111", "ZHANG^WEI", "2812", "201", "").is_err());
+        assert!(track2("4111111111111111", "12/28", "201", "").is_err());
+        assert!(track2("4111111111111111", "2812", "201", "1234567890123456789").is_err());
+    }
+}
@@ -0,0 +1,1058 @@
+//! 卡号校验。
+
+use crate::bin_database::{BinDatabase, BinInfo};
+use crate::card_generator::CreditCardGenerator;
+use crate::emv;
+use crate::tlv;
+use crate::networks::{CardNetworkRegistry, CheckDigitScheme, NetworkCandidate};
+use crate::track::{self, ParsedTrack};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// 验证失败原因，序列化为带稳定错误码的对象（如 `{"code": "luhn_failed"}`），
+/// 提示文字由 [`ValidationError::message`] 按语言单独生成
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum ValidationError {
+    /// 卡号中含有数字、空格与 `-` 以外的字符
+    NonDigitCharacters,
+    /// 无法识别网络，且长度不在通用的 13–19 位之间
+    InvalidLength { length: usize },
+    LuhnFailed,
+    UnknownNetwork,
+    /// 前缀属于某个网络，但该网络不允许这个长度
+    LengthNotAllowedForNetwork { network: String, length: usize, allowed: Vec<usize> },
+    /// 网络没有安全码，却提供了安全码
+    NoSecurityCode { network: String },
+    /// 安全码位数或字符不符合网络规则
+    InvalidSecurityCode { label: String, length: usize },
+    /// 网络使用 Luhn 以外的校验位算法，且校验失败
+    CheckDigitFailed { algorithm: String },
+    /// EMV 数据中 `5A` 与 `57` 的卡号不一致
+    EmvPanMismatch,
+    /// 有效期不是 MM/YY、MM/YYYY 或 MMYY
+    InvalidExpiry,
+    /// 有效期早于当前月份
+    Expired { expiry: String },
+    /// 持卡人姓名不符合磁道规则（2–26 个字母，只允许空格与 `/.-'`）
+    InvalidCardholderName,
+    /// 磁道数据无法解析
+    MalformedTrack { track: u8 },
+    /// 磁道末尾的 LRC 不正确
+    TrackLrcInvalid { track: u8 },
+    /// 磁道中的卡号与卡号字段不一致
+    TrackPanMismatch { track: u8 },
+    /// 磁道中的有效期与有效期字段不一致
+    TrackExpiryMismatch { track: u8 },
+    /// Track 1 中的姓名与持卡人姓名不一致
+    TrackNameMismatch,
+}
+
+/// 提示文字的语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Zh,
+    En,
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "zh" | "zh-cn" => Ok(Locale::Zh),
+            "en" | "en-us" => Ok(Locale::En),
+            _ => Err(format!("不支持的语言（应为 zh 或 en）: {}", s)),
+        }
+    }
+}
+
+impl ValidationError {
+    /// 稳定的错误码，与序列化结果中的 `code` 相同
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::NonDigitCharacters => "non_digit_characters",
+            ValidationError::InvalidLength { .. } => "invalid_length",
+            ValidationError::LuhnFailed => "luhn_failed",
+            ValidationError::UnknownNetwork => "unknown_network",
+            ValidationError::LengthNotAllowedForNetwork { .. } => "length_not_allowed_for_network",
+            ValidationError::NoSecurityCode { .. } => "no_security_code",
+            ValidationError::InvalidSecurityCode { .. } => "invalid_security_code",
+            ValidationError::CheckDigitFailed { .. } => "check_digit_failed",
+            ValidationError::EmvPanMismatch => "emv_pan_mismatch",
+            ValidationError::InvalidExpiry => "invalid_expiry",
+            ValidationError::Expired { .. } => "expired",
+            ValidationError::InvalidCardholderName => "invalid_cardholder_name",
+            ValidationError::MalformedTrack { .. } => "malformed_track",
+            ValidationError::TrackLrcInvalid { .. } => "track_lrc_invalid",
+            ValidationError::TrackPanMismatch { .. } => "track_pan_mismatch",
+            ValidationError::TrackExpiryMismatch { .. } => "track_expiry_mismatch",
+            ValidationError::TrackNameMismatch => "track_name_mismatch",
+        }
+    }
+
+    /// 指定语言的提示文字
+    pub fn message(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (ValidationError::NonDigitCharacters, Locale::Zh) => "卡号包含非数字字符".to_string(),
+            (ValidationError::NonDigitCharacters, Locale::En) => "Card number contains non-digit characters".to_string(),
+            (ValidationError::InvalidLength { .. }, Locale::Zh) => "卡号长度无效".to_string(),
+            (ValidationError::InvalidLength { length }, Locale::En) => {
+                format!("Card number must be 13-19 digits, got {}", length)
+            }
+            (ValidationError::LuhnFailed, Locale::Zh) => "Luhn 校验失败".to_string(),
+            (ValidationError::LuhnFailed, Locale::En) => "Luhn check failed".to_string(),
+            (ValidationError::UnknownNetwork, Locale::Zh) => "无法识别卡网络".to_string(),
+            (ValidationError::UnknownNetwork, Locale::En) => "Unknown card network".to_string(),
+            (ValidationError::LengthNotAllowedForNetwork { network, length, allowed }, Locale::Zh) => {
+                format!("{} 卡号应为 {} 位，实际为 {} 位", network, join_lengths(allowed), length)
+            }
+            (ValidationError::LengthNotAllowedForNetwork { network, length, allowed }, Locale::En) => {
+                format!("{} card numbers must be {} digits, got {}", network, join_lengths(allowed), length)
+            }
+            (ValidationError::NoSecurityCode { network }, Locale::Zh) => format!("{} 没有安全码", network),
+            (ValidationError::NoSecurityCode { network }, Locale::En) => format!("{} has no security code", network),
+            (ValidationError::InvalidSecurityCode { label, length }, Locale::Zh) => {
+                format!("{} 应为 {} 位数字", label, length)
+            }
+            (ValidationError::InvalidSecurityCode { label, length }, Locale::En) => {
+                format!("{} must be {} digits", label, length)
+            }
+            (ValidationError::CheckDigitFailed { algorithm }, Locale::Zh) => format!("{} 校验失败", algorithm),
+            (ValidationError::CheckDigitFailed { algorithm }, Locale::En) => format!("{} check failed", algorithm),
+            (ValidationError::EmvPanMismatch, Locale::Zh) => "5A 与 57 中的卡号不一致".to_string(),
+            (ValidationError::EmvPanMismatch, Locale::En) => "PAN in tag 5A does not match tag 57".to_string(),
+            (ValidationError::InvalidExpiry, Locale::Zh) => "有效期应为 MM/YY 或 MM/YYYY".to_string(),
+            (ValidationError::InvalidExpiry, Locale::En) => "Expiry must be MM/YY or MM/YYYY".to_string(),
+            (ValidationError::Expired { expiry }, Locale::Zh) => format!("卡片已过期（{}）", expiry),
+            (ValidationError::Expired { expiry }, Locale::En) => format!("Card expired ({})", expiry),
+            (ValidationError::InvalidCardholderName, Locale::Zh) => {
+                "持卡人姓名应为 2–26 个字母，只允许空格与 /.-'".to_string()
+            }
+            (ValidationError::InvalidCardholderName, Locale::En) => {
+                "Cardholder name must be 2-26 letters, spaces or /.-'".to_string()
+            }
+            (ValidationError::MalformedTrack { track }, Locale::Zh) => format!("Track {} 格式无效", track),
+            (ValidationError::MalformedTrack { track }, Locale::En) => format!("Track {} is malformed", track),
+            (ValidationError::TrackLrcInvalid { track }, Locale::Zh) => format!("Track {} 的 LRC 校验失败", track),
+            (ValidationError::TrackLrcInvalid { track }, Locale::En) => format!("Track {} LRC check failed", track),
+            (ValidationError::TrackPanMismatch { track }, Locale::Zh) => format!("Track {} 中的卡号与卡号不一致", track),
+            (ValidationError::TrackPanMismatch { track }, Locale::En) => {
+                format!("PAN in track {} does not match the card number", track)
+            }
+            (ValidationError::TrackExpiryMismatch { track }, Locale::Zh) => {
+                format!("Track {} 中的有效期与有效期不一致", track)
+            }
+            (ValidationError::TrackExpiryMismatch { track }, Locale::En) => {
+                format!("Expiry in track {} does not match the expiry", track)
+            }
+            (ValidationError::TrackNameMismatch, Locale::Zh) => "Track 1 中的姓名与持卡人姓名不一致".to_string(),
+            (ValidationError::TrackNameMismatch, Locale::En) => {
+                "Name in track 1 does not match the cardholder name".to_string()
+            }
+        }
+    }
+}
+
+fn join_lengths(lengths: &[usize]) -> String {
+    lengths.iter().map(usize::to_string).collect::<Vec<_>>().join("/")
+}
+
+/// 中文提示文字
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(Locale::Zh))
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// 有效结果的提示文字
+fn valid_message(locale: Locale) -> &'static str {
+    match locale {
+        Locale::Zh => "有效",
+        Locale::En => "Valid",
+    }
+}
+
+/// 卡号校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub valid: bool,
+    pub luhn_valid: bool,
+    /// 识别出的网络；长度不符合时为按前缀最匹配的网络
+    pub network: Option<String>,
+    /// 该网络允许的卡号长度，无法识别网络时为空
+    #[serde(default)]
+    pub allowed_lengths: Vec<usize>,
+    /// 所有匹配的网络，按具体程度排序；多于一个表示卡号落在重叠区间（如联名卡）
+    pub candidates: Vec<NetworkCandidate>,
+    /// 是否有多个网络匹配
+    pub ambiguous: bool,
+    /// 清理后的卡号位数
+    pub length: usize,
+    /// 输入中是否有被清理掉的字符（空格、`-`、字母等）
+    #[serde(default)]
+    pub cleaned: bool,
+    /// 中文提示文字（可用 [`ValidationResult::localize`] 换成其他语言），自动化测试请使用 `error`
+    pub reason: String,
+    /// 失败原因，有效时为空
+    #[serde(default)]
+    pub error: Option<ValidationError>,
+    /// BIN 元数据（发卡行、国家、卡类型），BIN 表中没有记录时为空
+    pub bin_info: Option<BinInfo>,
+    /// 安全码是否符合识别出的网络规则，未提供安全码时为空
+    #[serde(default)]
+    pub cvv_valid: Option<bool>,
+}
+
+impl ValidationResult {
+    /// 把 `reason` 换成指定语言的提示文字
+    pub fn localize(&mut self, locale: Locale) {
+        self.reason = match &self.error {
+            Some(error) => error.message(locale),
+            None => valid_message(locale).to_string(),
+        };
+    }
+
+    fn invalid(&mut self, error: ValidationError) {
+        self.valid = false;
+        self.reason = error.to_string();
+        self.error = Some(error);
+    }
+}
+
+/// 使用内置网络与内置 BIN 表验证信用卡号码
+pub fn validate_card_number(card_number: &str) -> ValidationResult {
+    validate_card_number_with(card_number, &CardNetworkRegistry::shared_builtin(), &BinDatabase::shared_builtin())
+}
+
+/// 使用指定的网络注册表与 BIN 表验证信用卡号码
+pub fn validate_card_number_with(
+    card_number: &str,
+    registry: &CardNetworkRegistry,
+    bin_database: &BinDatabase,
+) -> ValidationResult {
+    validate_card_with_cvv(card_number, None, registry, bin_database)
+}
+
+/// 验证卡号，并在提供安全码时按识别出的网络检查其位数（如 Amex CID 为 4 位）
+pub fn validate_card_with_cvv(
+    card_number: &str,
+    cvv: Option<&str>,
+    registry: &CardNetworkRegistry,
+    bin_database: &BinDatabase,
+) -> ValidationResult {
+    // 清理输入，只保留数字；去掉了任何字符（空格、`-`、字母等）时标记 `cleaned`
+    let clean_number: String = card_number.chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+    let cleaned = clean_number.len() != card_number.len();
+    let length = clean_number.len();
+    
+    // 检查 Luhn 算法
+    let luhn_valid = !clean_number.is_empty() && CreditCardGenerator::is_luhn_valid(&clean_number);
+    
+    // 识别网络：长度也匹配时为确定结果，否则按前缀给出最可能的网络
+    let detected = registry.detect(&clean_number);
+    let best_match = detected.or_else(|| registry.detect_by_prefix(&clean_number));
+    // 按网络选择的校验位算法检查，无法识别网络时按 Luhn
+    let scheme = best_match.map_or_else(CheckDigitScheme::luhn, |n| n.check_digit.clone());
+    let check_digit_valid = scheme.algorithm().is_valid(&clean_number);
+    let candidates = registry.detect_candidates(&clean_number);
+    
+    let cvv_error = match (cvv.map(str::trim), best_match) {
+        (Some(cvv), Some(network)) => Some(network.security_code_error(cvv)),
+        _ => None,
+    };
+    let cvv_valid = cvv_error.as_ref().map(Option::is_none);
+    
+    // 空格与 `-` 是常见的分组写法，其他字符视为输入错误；长度按网络规则检查，
+    // 无法按前缀识别网络时才使用通用的 13–19 位
+    let error = if card_number.chars().any(|c| !c.is_ascii_digit() && !c.is_whitespace() && c != '-') {
+        Some(ValidationError::NonDigitCharacters)
+    } else if let Some(network) = best_match.filter(|n| !n.is_valid_length(length)) {
+        Some(ValidationError::LengthNotAllowedForNetwork {
+            network: network.name.clone(),
+            length,
+            allowed: network.length.clone(),
+        })
+    } else if best_match.is_none() && !(13..=19).contains(&length) {
+        Some(ValidationError::InvalidLength { length })
+    } else if !check_digit_valid {
+        Some(if scheme == CheckDigitScheme::luhn() {
+            ValidationError::LuhnFailed
+        } else {
+            ValidationError::CheckDigitFailed { algorithm: scheme.algorithm().name().to_string() }
+        })
+    } else if best_match.is_none() {
+        Some(ValidationError::UnknownNetwork)
+    } else {
+        cvv_error.flatten()
+    };
+    
+    ValidationResult {
+        valid: error.is_none(),
+        luhn_valid,
+        network: best_match.map(|n| n.name.clone()),
+        allowed_lengths: best_match.map(|n| n.length.clone()).unwrap_or_default(),
+        ambiguous: candidates.len() > 1,
+        candidates,
+        length,
+        cleaned,
+        reason: error.as_ref().map_or_else(|| valid_message(Locale::Zh).to_string(), ToString::to_string),
+        error,
+        bin_info: bin_database.lookup(&clean_number).cloned(),
+        cvv_valid,
+    }
+}
+
+/// 解码粘贴的 EMV 数据（十六进制 BER-TLV）并验证其中的卡号（`5A`，没有时取 `57`）；
+/// `5A` 与 `57` 中的卡号不一致时视为无效
+pub fn validate_emv_data_with(
+    data: &str,
+    registry: &CardNetworkRegistry,
+    bin_database: &BinDatabase,
+) -> Result<ValidationResult, String> {
+    let tlvs = tlv::parse_hex(data)?;
+    let pan = emv::pan(&tlvs).ok_or_else(|| "EMV 数据中没有卡号（5A / 57）".to_string())?;
+    
+    let mut result = validate_card_number_with(&pan, registry, bin_database);
+    if emv::track2_pan(&tlvs).is_some_and(|track2| track2 != pan) {
+        result.invalid(ValidationError::EmvPanMismatch);
+    }
+    Ok(result)
+}
+
+/// 待验证的整条卡记录
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CardRecord {
+    pub number: String,
+    /// 有效期（MM/YY、MM/YYYY 或 MMYY）
+    pub expiry: String,
+    /// 安全码；网络没有安全码时留空
+    pub cvv: String,
+    pub cardholder: Option<String>,
+    pub track1: Option<String>,
+    pub track2: Option<String>,
+}
+
+/// 卡记录中的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordField {
+    Number,
+    Expiry,
+    Cvv,
+    Cardholder,
+    Track1,
+    Track2,
+}
+
+/// 单个字段的验证结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldValidation {
+    pub field: RecordField,
+    pub valid: bool,
+    /// 中文提示文字（可用 [`RecordValidation::localize`] 换成其他语言）
+    pub reason: String,
+    /// 失败原因，有效时为空
+    #[serde(default)]
+    pub error: Option<ValidationError>,
+}
+
+impl FieldValidation {
+    fn new(field: RecordField, error: Option<ValidationError>) -> Self {
+        Self {
+            field,
+            valid: error.is_none(),
+            reason: error.as_ref().map_or_else(|| valid_message(Locale::Zh).to_string(), ToString::to_string),
+            error,
+        }
+    }
+}
+
+/// 整条卡记录的验证结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordValidation {
+    /// 所有字段均有效
+    pub valid: bool,
+    /// 卡号的完整验证结果（网络、BIN 元数据等）
+    pub card: ValidationResult,
+    /// 逐字段结果，未提供的可选字段（姓名、磁道）不出现
+    pub fields: Vec<FieldValidation>,
+}
+
+impl RecordValidation {
+    /// 把卡号与逐字段结果中的提示文字换成指定语言
+    pub fn localize(&mut self, locale: Locale) {
+        self.card.localize(locale);
+        for field in &mut self.fields {
+            field.reason = match &field.error {
+                Some(error) => error.message(locale),
+                None => valid_message(locale).to_string(),
+            };
+        }
+    }
+}
+
+/// 验证整条卡记录：卡号、有效期（格式及是否早于 `today` 所在月份）、安全码位数、
+/// 持卡人姓名，以及磁道数据与卡号、有效期、姓名是否一致
+pub fn validate_card_record(
+    record: &CardRecord,
+    today: NaiveDate,
+    registry: &CardNetworkRegistry,
+    bin_database: &BinDatabase,
+) -> RecordValidation {
+    let card = validate_card_number_with(&record.number, registry, bin_database);
+    let pan: String = record.number.chars().filter(|c| c.is_ascii_digit()).collect();
+    let mut fields = vec![FieldValidation::new(RecordField::Number, card.error.clone())];
+
+    let expiry = parse_expiry(&record.expiry);
+    let expiry_error = match expiry {
+        None => Some(ValidationError::InvalidExpiry),
+        Some((month, year)) if (year, month) < (today.year(), today.month()) => {
+            Some(ValidationError::Expired { expiry: format!("{:02}/{:02}", month, year % 100) })
+        }
+        Some(_) => None,
+    };
+    fields.push(FieldValidation::new(RecordField::Expiry, expiry_error));
+
+    let cvv = record.cvv.trim();
+    let cvv_error = match registry.detect(&pan).or_else(|| registry.detect_by_prefix(&pan)) {
+        Some(network) if network.cvv_length == 0 && cvv.is_empty() => None,
+        Some(network) => network.security_code_error(cvv),
+        None => Some(ValidationError::UnknownNetwork),
+    };
+    fields.push(FieldValidation::new(RecordField::Cvv, cvv_error));
+
+    let cardholder = record.cardholder.as_deref().map(str::trim);
+    if let Some(name) = cardholder {
+        let name_valid = (2..=26).contains(&name.len())
+            && name.chars().all(|c| c.is_ascii_alphabetic() || " /.-'".contains(c));
+        let error = (!name_valid).then_some(ValidationError::InvalidCardholderName);
+        fields.push(FieldValidation::new(RecordField::Cardholder, error));
+    }
+
+    // 磁道中的有效期为 YYMM
+    let expiry_yymm = expiry.map(|(month, year)| format!("{:02}{:02}", year % 100, month));
+    if let Some(data) = &record.track1 {
+        let parsed = track::parse_track1(data);
+        let error = check_track(1, &parsed, &pan, expiry_yymm.as_deref()).or_else(|| {
+            let track_name = parsed.as_ref().ok()?.name.as_deref()?;
+            (name_key(track_name) != name_key(cardholder?)).then_some(ValidationError::TrackNameMismatch)
+        });
+        fields.push(FieldValidation::new(RecordField::Track1, error));
+    }
+    if let Some(data) = &record.track2 {
+        let error = check_track(2, &track::parse_track2(data), &pan, expiry_yymm.as_deref());
+        fields.push(FieldValidation::new(RecordField::Track2, error));
+    }
+
+    RecordValidation {
+        valid: fields.iter().all(|field| field.valid),
+        card,
+        fields,
+    }
+}
+
+/// 解析 MM/YY、MM/YYYY、MM-YY 或 MMYY，返回（月, 四位年份）
+fn parse_expiry(expiry: &str) -> Option<(u32, i32)> {
+    let expiry = expiry.trim();
+    let (month, year) = match expiry.split_once(['/', '-']) {
+        Some((month, year)) => (month.trim(), year.trim()),
+        None if expiry.len() == 4 => expiry.split_at_checked(2)?,
+        None => return None,
+    };
+    if !(1..=2).contains(&month.len()) || !matches!(year.len(), 2 | 4) {
+        return None;
+    }
+    if !month.chars().chain(year.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let month: u32 = month.parse().ok().filter(|m| (1..=12).contains(m))?;
+    let year: i32 = year.parse().ok()?;
+    Some((month, if year < 100 { 2000 + year } else { year }))
+}
+
+fn check_track(
+    number: u8,
+    parsed: &Result<ParsedTrack, String>,
+    pan: &str,
+    expiry_yymm: Option<&str>,
+) -> Option<ValidationError> {
+    let Ok(parsed) = parsed else {
+        return Some(ValidationError::MalformedTrack { track: number });
+    };
+    if parsed.lrc_valid == Some(false) {
+        Some(ValidationError::TrackLrcInvalid { track: number })
+    } else if parsed.pan != pan {
+        Some(ValidationError::TrackPanMismatch { track: number })
+    } else if expiry_yymm.is_some_and(|expiry| expiry != parsed.expiry) {
+        Some(ValidationError::TrackExpiryMismatch { track: number })
+    } else {
+        None
+    }
+}
+
+/// 比较姓名时忽略大小写，并把磁道的 `姓/名` 写法换成 `名 姓`
+fn name_key(name: &str) -> String {
+    let name = name.trim().to_uppercase();
+    let name = match name.split_once('/') {
+        Some((surname, given)) => format!("{} {}", given.trim(), surname.trim()),
+        None => name,
+    };
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 批量验证中一条输入的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineValidation {
+    /// 行号（从 1 开始；JSON 输入为数组下标 + 1）
+    pub line: usize,
+    /// 从该行取出的卡号原文
+    pub input: String,
+    pub result: ValidationResult,
+}
+
+/// 某个网络的有效 / 无效数量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidityCount {
+    pub valid: usize,
+    pub invalid: usize,
+}
+
+/// 批量验证汇总中无法识别网络的卡号所用的键
+pub const UNKNOWN_NETWORK: &str = "unknown";
+
+/// 批量验证汇总
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationSummary {
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    /// 无效原因（提示文字）及其数量
+    pub by_reason: BTreeMap<String, usize>,
+    /// 无效原因的错误码（见 [`ValidationError::code`]）及其数量
+    #[serde(default)]
+    pub by_code: BTreeMap<String, usize>,
+    /// 按识别出的网络名称统计，无法识别的记在 [`UNKNOWN_NETWORK`] 下
+    pub by_network: BTreeMap<String, ValidityCount>,
+    /// `by_network` 各键的展示文字，[`UNKNOWN_NETWORK`] 随语言给出
+    #[serde(default)]
+    pub network_labels: BTreeMap<String, String>,
+}
+
+impl ValidationSummary {
+    /// 按指定语言重新生成 `network_labels`
+    fn label_networks(&mut self, locale: Locale) {
+        self.network_labels = self
+            .by_network
+            .keys()
+            .map(|network| {
+                let label = if network == UNKNOWN_NETWORK {
+                    ValidationError::UnknownNetwork.message(locale)
+                } else {
+                    network.clone()
+                };
+                (network.clone(), label)
+            })
+            .collect();
+    }
+}
+
+/// 批量验证结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchValidation {
+    /// 实际使用的输入格式（PIPE / CSV / JSON）
+    pub format: String,
+    pub results: Vec<LineValidation>,
+    pub summary: ValidationSummary,
+}
+
+impl BatchValidation {
+    /// 把逐条结果与汇总中的提示文字、网络展示文字换成指定语言
+    pub fn localize(&mut self, locale: Locale) {
+        self.summary.label_networks(locale);
+        self.summary.by_reason.clear();
+        for line in &mut self.results {
+            line.result.localize(locale);
+            if !line.result.valid {
+                *self.summary.by_reason.entry(line.result.reason.clone()).or_default() += 1;
+            }
+        }
+    }
+}
+
+/// 批量验证卡号列表
+pub fn validate_card_list<S: AsRef<str>>(
+    card_numbers: &[S],
+    registry: &CardNetworkRegistry,
+    bin_database: &BinDatabase,
+) -> BatchValidation {
+    let entries = card_numbers
+        .iter()
+        .enumerate()
+        .map(|(index, number)| (index + 1, number.as_ref().trim().to_string()))
+        .collect();
+    validate_entries("PIPE", entries, registry, bin_database)
+}
+
+/// 批量验证整段文本（如从日志或导出文件中粘贴的内容），逐条给出结果与汇总
+///
+/// 支持 PIPE（每行取第一个 `|` 之前的部分，也适用于每行一个卡号）、CSV（表头含
+/// `number` 列）与 JSON（卡数据数组、卡号字符串数组）。`format` 为空时自动识别：
+/// 以 `[` 开头视为 JSON，首行含 `number` 列视为 CSV，其余按 PIPE 处理。
+pub fn validate_cards(
+    text: &str,
+    format: Option<&str>,
+    registry: &CardNetworkRegistry,
+    bin_database: &BinDatabase,
+) -> Result<BatchValidation, String> {
+    let format = match format {
+        Some(format) => format.to_uppercase(),
+        None => detect_input_format(text).to_string(),
+    };
+    let entries = match format.as_str() {
+        "PIPE" => pipe_entries(text),
+        "CSV" => csv_entries(text)?,
+        "JSON" => json_entries(text)?,
+        _ => return Err(format!("不支持的输入格式: {}（应为 PIPE / CSV / JSON）", format)),
+    };
+    Ok(validate_entries(&format, entries, registry, bin_database))
+}
+
+fn detect_input_format(text: &str) -> &'static str {
+    let text = text.trim_start();
+    let first_line = text.lines().next().unwrap_or("");
+    if text.starts_with('[') {
+        "JSON"
+    } else if first_line.split(',').any(|column| column.trim().trim_matches('"') == "number") {
+        "CSV"
+    } else {
+        "PIPE"
+    }
+}
+
+fn pipe_entries(text: &str) -> Vec<(usize, String)> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let number = line.split('|').next().unwrap_or("").trim();
+            (!number.is_empty()).then(|| (index + 1, number.to_string()))
+        })
+        .collect()
+}
+
+fn csv_entries(text: &str) -> Result<Vec<(usize, String)>, String> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(text.as_bytes());
+    let column = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .position(|header| header.trim() == "number")
+        .ok_or_else(|| "CSV 表头中没有 number 列".to_string())?;
+
+    let mut entries = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("CSV 解析失败: {}", e))?;
+        let line = record.position().map_or(0, |p| p.line() as usize);
+        let number = record.get(column).unwrap_or("").trim();
+        if !number.is_empty() {
+            entries.push((line, number.to_string()));
+        }
+    }
+    Ok(entries)
+}
+
+fn json_entries(text: &str) -> Result<Vec<(usize, String)>, String> {
+    let values: Vec<Value> = serde_json::from_str(text).map_err(|e| format!("JSON 解析失败: {}", e))?;
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let number = match value {
+                Value::String(number) => Some(number.as_str()),
+                Value::Object(card) => card.get("number").and_then(Value::as_str),
+                _ => None,
+            };
+            number
+                .map(|number| (index + 1, number.trim().to_string()))
+                .ok_or_else(|| format!("JSON 第 {} 项既不是卡号字符串，也没有 number 字段", index + 1))
+        })
+        .collect()
+}
+
+fn validate_entries(
+    format: &str,
+    entries: Vec<(usize, String)>,
+    registry: &CardNetworkRegistry,
+    bin_database: &BinDatabase,
+) -> BatchValidation {
+    let mut summary = ValidationSummary::default();
+    let results = entries
+        .into_iter()
+        .map(|(line, input)| {
+            let result = validate_card_number_with(&input, registry, bin_database);
+            summary.total += 1;
+            let network = summary
+                .by_network
+                .entry(result.network.clone().unwrap_or_else(|| UNKNOWN_NETWORK.to_string()))
+                .or_default();
+            if result.valid {
+                summary.valid += 1;
+                network.valid += 1;
+            } else {
+                summary.invalid += 1;
+                network.invalid += 1;
+                *summary.by_reason.entry(result.reason.clone()).or_default() += 1;
+                if let Some(error) = &result.error {
+                    *summary.by_code.entry(error.code().to_string()).or_default() += 1;
+                }
+            }
+            LineValidation { line, input, result }
+        })
+        .collect();
+    summary.label_networks(Locale::Zh);
+
+    BatchValidation {
+        format: format.to_string(),
+        results,
+        summary,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    
+    #[test]
+    fn test_validate_visa() {
+        let result = validate_card_number("4111111111111111");
+        assert!(result.valid);
+        assert!(result.luhn_valid);
+        assert_eq!(result.network, Some("Visa".to_string()));
+    }
+    
+    #[test]
+    fn test_validate_mastercard() {
+        let result = validate_card_number("5555555555554444");
+        assert!(result.valid);
+        assert!(result.luhn_valid);
+        assert_eq!(result.network, Some("Mastercard".to_string()));
+    }
+    
+    #[test]
+    fn test_validate_invalid() {
+        let result = validate_card_number("1234567890123456");
+        assert!(!result.valid);
+    }
+    
+    #[test]
+    fn test_validate_invalid_length() {
+        let result = validate_card_number("123");
+        assert!(!result.valid);
+        assert_eq!(result.reason, "卡号长度无效");
+    }
+
+    #[test]
+    fn test_builtin_tables_are_shared() {
+        // 内置注册表与 BIN 表只构建一次，之后的验证直接复用
+        assert!(Arc::ptr_eq(&CardNetworkRegistry::shared_builtin(), &CardNetworkRegistry::shared_builtin()));
+        assert!(Arc::ptr_eq(&BinDatabase::shared_builtin(), &BinDatabase::shared_builtin()));
+        assert_eq!(*BinDatabase::shared_builtin(), BinDatabase::builtin());
+        assert!(validate_card_number("4111111111111111").valid);
+    }
+
+    #[test]
+    fn test_validate_with_custom_registry() {
+        let registry = CardNetworkRegistry::from_toml_str(
+            r#"
+            [[networks]]
+            name = "Gift"
+            identifier = "gift"
+            ranges = ["9"]
+            length = [16]
+            cvv_length = 0
+            check_digit = "none"
+            "#,
+        )
+        .unwrap();
+        
+        let result = validate_card_number_with("9000000000000002", &registry, &BinDatabase::empty());
+        assert!(result.valid);
+        assert!(!result.luhn_valid);
+        assert_eq!(result.network, Some("Gift".to_string()));
+        
+        assert!(!validate_card_number_with("4111111111111111", &registry, &BinDatabase::empty()).valid);
+    }
+    
+    #[test]
+    fn test_validate_reports_candidates() {
+        let result = validate_card_number("6221261111111111");
+        assert_eq!(result.network, Some("Discover".to_string()));
+        assert!(result.ambiguous);
+        assert_eq!(result.candidates.len(), 2);
+        assert_eq!(result.candidates[1].identifier, "unionpay");
+        
+        let result = validate_card_number("4111111111111111");
+        assert!(!result.ambiguous);
+        assert_eq!(result.candidates.len(), 1);
+    }
+    
+    #[test]
+    fn test_validate_cvv() {
+        let registry = CardNetworkRegistry::builtin();
+        let bins = BinDatabase::empty();
+        
+        let result = validate_card_with_cvv("378282246310005", Some("1234"), &registry, &bins);
+        assert!(result.valid);
+        assert_eq!(result.cvv_valid, Some(true));
+        
+        let result = validate_card_with_cvv("378282246310005", Some("123"), &registry, &bins);
+        assert!(!result.valid);
+        assert_eq!(result.cvv_valid, Some(false));
+        assert_eq!(result.reason, "CID 应为 4 位数字");
+        
+        assert!(validate_card_with_cvv("4111111111111111", Some("123"), &registry, &bins).valid);
+        assert_eq!(validate_card_number("4111111111111111").cvv_valid, None);
+    }
+    
+    #[test]
+    fn test_validate_emv_data() {
+        let registry = CardNetworkRegistry::builtin();
+        let bins = BinDatabase::empty();
+        
+        let result = validate_emv_data_with("70 0A 5A 08 41 11 11 11 11 11 11 11", &registry, &bins).unwrap();
+        assert!(result.valid);
+        assert_eq!(result.network, Some("Visa".to_string()));
+        
+        let mismatch = "700F5A0841111111111111115703411111";
+        let result = validate_emv_data_with(mismatch, &registry, &bins).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.reason, "5A 与 57 中的卡号不一致");
+        
+        assert!(validate_emv_data_with("9F0702FF00", &registry, &bins).is_err());
+        assert!(validate_emv_data_with("5A08", &registry, &bins).is_err());
+    }
+    
+    #[test]
+    fn test_validation_error_codes() {
+        let result = validate_card_number("4111111111111112");
+        assert_eq!(result.error, Some(ValidationError::LuhnFailed));
+        assert_eq!(serde_json::to_value(&result.error).unwrap(), serde_json::json!({ "code": "luhn_failed" }));
+        
+        assert_eq!(validate_card_number("123").error, Some(ValidationError::InvalidLength { length: 3 }));
+        assert_eq!(validate_card_number("4111-1111-1111-1111").error, None);
+        assert_eq!(validate_card_number("4111 1111 1111 111O").error, Some(ValidationError::NonDigitCharacters));
+        assert_eq!(validate_card_number("9999999999999995").error, Some(ValidationError::UnknownNetwork));
+        
+        // 前缀属于 Amex（34 / 37），但 Amex 只允许 15 位
+        let result = validate_card_number("3400000000000000");
+        let error = result.error.unwrap();
+        assert_eq!(error, ValidationError::LengthNotAllowedForNetwork {
+            network: "American Express".to_string(),
+            length: 16,
+            allowed: vec![15],
+        });
+        assert_eq!(error.code(), "length_not_allowed_for_network");
+        assert_eq!(serde_json::to_value(&error).unwrap()["code"], "length_not_allowed_for_network");
+        
+        let registry = CardNetworkRegistry::builtin();
+        let mut result = validate_card_with_cvv("378282246310005", Some("123"), &registry, &BinDatabase::empty());
+        assert_eq!(result.error.as_ref().map(ValidationError::code), Some("invalid_security_code"));
+        result.localize(Locale::En);
+        assert_eq!(result.reason, "CID must be 4 digits");
+        
+        let mut result = validate_card_number("4111111111111111");
+        result.localize(Locale::En);
+        assert_eq!(result.reason, "Valid");
+        assert_eq!("en".parse::<Locale>().unwrap(), Locale::En);
+    }
+    
+    #[test]
+    fn test_network_aware_length() {
+        // 15 位且以 4 开头：提示 Visa 的长度，而不是无法识别网络
+        let result = validate_card_number("411111111111113");
+        assert!(!result.valid);
+        assert_eq!(result.network.as_deref(), Some("Visa"));
+        assert_eq!(result.allowed_lengths, vec![13, 16, 19]);
+        assert_eq!(result.error.as_ref().map(ValidationError::code), Some("length_not_allowed_for_network"));
+        assert_eq!(result.reason, "Visa 卡号应为 13/16/19 位，实际为 15 位");
+        
+        let result = validate_card_number("4111-1111-1111-1111");
+        assert!(result.valid && result.cleaned);
+        assert_eq!(result.allowed_lengths, vec![13, 16, 19]);
+        assert!(!validate_card_number("4111111111111111").cleaned);
+        
+        let result = validate_card_number("4111 1111 1111 111O");
+        assert!(result.cleaned);
+        assert_eq!(result.length, 15);
+        assert_eq!(result.error, Some(ValidationError::NonDigitCharacters));
+        
+        // 自定义网络的长度不受通用 13–19 位限制
+        let registry = CardNetworkRegistry::from_toml_str(
+            r#"
+            [[networks]]
+            name = "Loyalty"
+            identifier = "loyalty"
+            ranges = ["8"]
+            length = [10]
+            cvv_length = 0
+            "#,
+        )
+        .unwrap();
+        let result = validate_card_number_with("8000000003", &registry, &BinDatabase::empty());
+        assert!(result.valid, "{}", result.reason);
+        assert_eq!(result.allowed_lengths, vec![10]);
+        
+        let result = validate_card_number("");
+        assert_eq!(result.error, Some(ValidationError::InvalidLength { length: 0 }));
+        assert!(!result.luhn_valid);
+    }
+    
+    #[test]
+    fn test_validate_cards_batch() {
+        let registry = CardNetworkRegistry::builtin();
+        let bins = BinDatabase::empty();
+        
+        let pipe = "4111111111111111|12/28|123\n\n4111111111111112|12/28\n378282246310005\n123\n";
+        let batch = validate_cards(pipe, None, &registry, &bins).unwrap();
+        assert_eq!(batch.format, "PIPE");
+        assert_eq!(batch.results.iter().map(|r| r.line).collect::<Vec<_>>(), vec![1, 3, 4, 5]);
+        assert_eq!(batch.summary.total, 4);
+        assert_eq!(batch.summary.valid, 2);
+        assert_eq!(batch.summary.by_reason["Luhn 校验失败"], 1);
+        assert_eq!(batch.summary.by_reason["卡号长度无效"], 1);
+        assert_eq!(batch.summary.by_code["luhn_failed"], 1);
+        assert_eq!(batch.summary.by_code["invalid_length"], 1);
+        assert_eq!(batch.summary.by_network["Visa"], ValidityCount { valid: 1, invalid: 1 });
+        assert_eq!(batch.summary.by_network[UNKNOWN_NETWORK], ValidityCount { valid: 0, invalid: 1 });
+        assert_eq!(batch.summary.network_labels[UNKNOWN_NETWORK], "无法识别卡网络");
+        assert_eq!(batch.summary.network_labels["Visa"], "Visa");
+
+        // 切换语言后网络键保持不变，展示文字与无效原因随语言变化
+        let mut batch = batch;
+        batch.localize(Locale::En);
+        assert_eq!(batch.summary.by_network[UNKNOWN_NETWORK], ValidityCount { valid: 0, invalid: 1 });
+        assert_eq!(batch.summary.network_labels[UNKNOWN_NETWORK], "Unknown card network");
+        assert_eq!(batch.summary.by_reason["Luhn check failed"], 1);
+        assert_eq!(batch.results[1].result.reason, "Luhn check failed");
+        assert_eq!(batch.summary.by_code["luhn_failed"], 1);
+        
+        let csv = "number,network,exp_month\n4111111111111111,Visa,12\n5555555555554444,Mastercard,01\n";
+        let batch = validate_cards(csv, None, &registry, &bins).unwrap();
+        assert_eq!(batch.format, "CSV");
+        assert_eq!(batch.results[1].line, 3);
+        assert_eq!(batch.summary.valid, 2);
+        
+        let json = r#"[{"number": "5555555555554444", "network": "Mastercard"}, "4111111111111112"]"#;
+        let batch = validate_cards(json, None, &registry, &bins).unwrap();
+        assert_eq!(batch.format, "JSON");
+        assert_eq!(batch.summary.by_network["Mastercard"].valid, 1);
+        assert_eq!(batch.results[1].line, 2);
+        assert!(!batch.results[1].result.valid);
+        
+        assert!(validate_cards("[1, 2]", None, &registry, &bins).is_err());
+        assert!(validate_cards("4111111111111111", Some("XML"), &registry, &bins).is_err());
+        
+        let batch = validate_card_list(&["4111111111111111", " 5555555555554444 "], &registry, &bins);
+        assert_eq!(batch.summary.valid, 2);
+        
+        let mut batch = validate_card_list(&["4111111111111112", "123"], &registry, &bins);
+        batch.localize(Locale::En);
+        assert_eq!(batch.summary.by_reason["Luhn check failed"], 1);
+        assert_eq!(batch.results[1].result.reason, "Card number must be 13-19 digits, got 3");
+    }
+    
+    #[test]
+    fn test_validate_card_record() {
+        let registry = CardNetworkRegistry::builtin();
+        let bins = BinDatabase::empty();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let track1 = track::track1("4111111111111111", "ZHANG/WEI", "2812", "201", "").unwrap();
+        let record = CardRecord {
+            number: "4111 1111 1111 1111".to_string(),
+            expiry: "12/28".to_string(),
+            cvv: "123".to_string(),
+            cardholder: Some("Wei Zhang".to_string()),
+            track1: Some(track1.clone()),
+            track2: Some(";4111111111111111=2812201?".to_string()),
+        };
+        let result = validate_card_record(&record, today, &registry, &bins);
+        assert!(result.valid, "{:?}", result.fields);
+        let fields: Vec<RecordField> = result.fields.iter().map(|f| f.field).collect();
+        assert_eq!(fields, vec![
+            RecordField::Number,
+            RecordField::Expiry,
+            RecordField::Cvv,
+            RecordField::Cardholder,
+            RecordField::Track1,
+            RecordField::Track2,
+        ]);
+        
+        // 当月仍然有效，上个月已过期
+        let current = CardRecord { expiry: "10/2026".to_string(), track1: None, track2: None, ..record.clone() };
+        assert!(validate_card_record(&current, today, &registry, &bins).valid);
+        let expired = CardRecord { expiry: "0926".to_string(), ..current.clone() };
+        let mut result = validate_card_record(&expired, today, &registry, &bins);
+        assert!(!result.valid);
+        assert_eq!(result.fields[1].error, Some(ValidationError::Expired { expiry: "09/26".to_string() }));
+        result.localize(Locale::En);
+        assert_eq!(result.fields[1].reason, "Card expired (09/26)");
+        assert_eq!(result.fields[0].reason, "Valid");
+        
+        let errors = |record: &CardRecord| -> Vec<Option<&'static str>> {
+            validate_card_record(record, today, &registry, &bins)
+                .fields
+                .iter()
+                .map(|f| f.error.as_ref().map(ValidationError::code))
+                .collect()
+        };
+        let bad = CardRecord {
+            number: "378282246310005".to_string(),
+            expiry: "13/28".to_string(),
+            cvv: "123".to_string(),
+            cardholder: Some("Z".to_string()),
+            track1: Some(track1.clone()),
+            track2: Some(";4111111111111111=2812201?".to_string()),
+        };
+        assert_eq!(errors(&bad), vec![
+            None,
+            Some("invalid_expiry"),
+            Some("invalid_security_code"),
+            Some("invalid_cardholder_name"),
+            Some("track_pan_mismatch"),
+            Some("track_pan_mismatch"),
+        ]);
+        
+        let mismatched = CardRecord {
+            cardholder: Some("LI/NA".to_string()),
+            track1: Some(track1.replacen("2812", "2811", 1)),
+            track2: Some("4111111111111111=2812201?".to_string()),
+            ..record.clone()
+        };
+        assert_eq!(&errors(&mismatched)[3..], &[None, Some("track_lrc_invalid"), Some("malformed_track")]);
+        
+        let other_name = CardRecord { cardholder: Some("LI/NA".to_string()), ..record.clone() };
+        assert_eq!(errors(&other_name)[4], Some("track_name_mismatch"));
+        let other_expiry = CardRecord { expiry: "11/28".to_string(), ..record.clone() };
+        assert_eq!(&errors(&other_expiry)[4..], &[Some("track_expiry_mismatch"), Some("track_expiry_mismatch")]);
+    }
+    
+    #[test]
+    fn test_validate_reports_issuer() {
+        let result = validate_card_number("4111 1111 1111 1111");
+        let info = result.bin_info.unwrap();
+        assert_eq!(info.issuer, "Sample Test Bank");
+        assert_eq!(info.country, "US");
+    }
+}
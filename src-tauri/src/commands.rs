@@ -1,13 +1,73 @@
-use crate::card_generator::{CardData, CreditCardGenerator};
-use crate::exporters::{export_to_card, export_to_csv, export_to_json, export_to_pipe, export_to_sql, export_to_xml};
-use crate::fake_data::{generate_fake_users, generate_lorem_ipsum, FakeUser};
-use crate::networks::CardNetworkRegistry;
-use crate::validator::{validate_card_number, ValidationResult};
-use tauri::State;
-use std::sync::Mutex;
+use namso_core::bin_database::{BinCriteria, BinDatabase, BinInfo};
+use namso_core::card_generator::{CardData, CreditCardGenerator, GenerateOptions};
+use namso_core::clock::FixedClock;
+use namso_core::cvv::{CvkPair, CvvKind};
+use namso_core::expiry::ExpiryPolicy;
+use namso_core::exporters::{export_cards_as, CardExporter};
+use namso_core::fake_data::{generate_fake_users, generate_lorem_ipsum, FakeUser};
+use namso_core::fpe::{FpeKey, FpeMode, PanCipher};
+use namso_core::history::CardHistory;
+use namso_core::networks::{CardNetwork, CardNetworkRegistry};
+use namso_core::token::{TokenOptions, TokenRecord, TokenVault};
+use namso_core::emv::{self, EmvTag};
+use namso_core::validator::{
+    validate_card_list, validate_card_record as validate_record, validate_card_with_cvv,
+    validate_cards as validate_card_text, validate_emv_data_with, BatchValidation, CardRecord, Locale,
+    RecordValidation, ValidationResult,
+};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// 生成进度事件名
+pub const GENERATION_PROGRESS_EVENT: &str = "generation-progress";
+
+/// 每生成多少张卡报告一次进度
+const PROGRESS_INTERVAL: usize = 10_000;
+
+const CANCELLED: &str = "生成已取消";
 
 pub struct AppState {
-    pub generator: Mutex<CreditCardGenerator>,
+    /// 共享的网络注册表与 BIN 表；命令只在取快照或修改时短暂加锁，生成在各自的生成器上进行
+    pub catalog: RwLock<CreditCardGenerator>,
+    /// 正在进行的文件生成任务及其取消标记
+    pub jobs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl AppState {
+    pub fn new(catalog: CreditCardGenerator) -> Self {
+        Self {
+            catalog: RwLock::new(catalog),
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 为一次请求创建独立的生成器，指定日期（YYYY-MM-DD）时以该日期代替今天计算过期日期；
+    /// 指定种子时必须同时指定日期，过期日期按调用方给出的日期计算，相同请求任何时候都得到相同结果
+    fn generator(&self, seed: Option<u64>, today: Option<&str>) -> Result<CreditCardGenerator, String> {
+        let catalog = self.catalog.read().map_err(|e| e.to_string())?;
+        let today = today.map(str::parse::<FixedClock>).transpose()?;
+        Ok(match (seed, today) {
+            (Some(seed), Some(clock)) => catalog.fork(seed).with_clock(clock),
+            (Some(_), None) => return Err("指定 seed 时须同时指定 today（YYYY-MM-DD），过期日期按该日期计算".to_string()),
+            (None, Some(clock)) => catalog.fork_from_entropy().with_clock(clock),
+            (None, None) => catalog.fork_from_entropy(),
+        })
+    }
+}
+
+/// 文件生成任务的进度
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationProgress {
+    pub job_id: String,
+    pub generated: usize,
+    pub total: usize,
+    pub done: bool,
+    pub cancelled: bool,
 }
 
 /// 生成信用卡数据
@@ -15,32 +75,341 @@ pub struct AppState {
 pub async fn generate_cards(
     network: String,
     quantity: usize,
+    expiry: Option<ExpiryPolicy>,
     exp_month: Option<u32>,
     exp_year: Option<i32>,
     include_cvv: bool,
+    cvv_length: Option<usize>,
+    cvk: Option<CvkPair>,
     include_balance: bool,
     currency: Option<String>,
     bin_code: Option<String>,
+    criteria: Option<BinCriteria>,
+    seed: Option<u64>,
+    today: Option<String>,
+    unique: Option<bool>,
+    history_file: Option<String>,
+    parallel: Option<bool>,
+    include_tracks: Option<bool>,
+    service_code: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<Vec<CardData>, String> {
-    let generator = state.generator.lock().map_err(|e| e.to_string())?;
+    let mut generator = state.generator(seed, today.as_deref())?;
 
-    generator.generate_card_data(
-        &network,
+    // 指定历史文件时跨批次去重，并在生成后写回
+    let mut history = match &history_file {
+        Some(path) => CardHistory::load(path)?,
+        None => CardHistory::new(),
+    };
+    let options = GenerateOptions {
+        network,
         quantity,
+        expiry,
         exp_month,
         exp_year,
         include_cvv,
+        cvv_length,
+        cvk,
         include_balance,
         currency,
         bin_code,
-    )
+        criteria: criteria.unwrap_or_default(),
+        unique: unique.unwrap_or(false) || history_file.is_some(),
+        include_tracks: include_tracks.unwrap_or(false),
+        service_code,
+    };
+    let cards = if parallel.unwrap_or(false) {
+        generator.generate_card_data_parallel_with_history(&options, &mut history)?
+    } else {
+        generator.generate_card_data_with_history(&options, &mut history)?
+    };
+
+    if let Some(path) = &history_file {
+        history.save(path)?;
+    }
+    Ok(cards)
+}
+
+/// 大批量生成并直接写入文件，通过 `generation-progress` 事件报告进度，返回写入的数量
+#[tauri::command(rename_all = "snake_case")]
+pub async fn generate_cards_to_file(
+    job_id: String,
+    options: GenerateOptions,
+    seed: Option<u64>,
+    today: Option<String>,
+    parallel: Option<bool>,
+    path: String,
+    format: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let mut generator = state.generator(seed, today.as_deref())?;
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    state.jobs.lock().map_err(|e| e.to_string())?
+        .insert(job_id.clone(), cancelled.clone());
+
+    let job = job_id.clone();
+    let parallel = parallel.unwrap_or(false);
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        write_cards_to_file(&mut generator, &options, parallel, &path, &format, &job, &cancelled, &app)
+    })
+    .await
+    .map_err(|e| e.to_string());
+
+    state.jobs.lock().map_err(|e| e.to_string())?.remove(&job_id);
+    result?
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_cards_to_file(
+    generator: &mut CreditCardGenerator,
+    options: &GenerateOptions,
+    parallel: bool,
+    path: &str,
+    format: &str,
+    job_id: &str,
+    cancelled: &AtomicBool,
+    app: &AppHandle,
+) -> Result<usize, String> {
+    let total = options.quantity;
+    let report = |generated: usize, done: bool, cancelled: bool| {
+        let _ = app.emit(GENERATION_PROGRESS_EVENT, GenerationProgress {
+            job_id: job_id.to_string(),
+            generated,
+            total,
+            done,
+            cancelled,
+        });
+    };
+
+    let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut exporter = CardExporter::new(BufWriter::new(file), format)?;
+    let mut write = |card: &CardData| {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(CANCELLED.to_string());
+        }
+        exporter.write_card(card)?;
+        if exporter.written() % PROGRESS_INTERVAL == 0 {
+            report(exporter.written(), false, false);
+        }
+        Ok(())
+    };
+
+    let result = if parallel {
+        generator.generate_card_chunks_parallel(options, &mut CardHistory::new(), |chunk| {
+            chunk.iter().try_for_each(&mut write)
+        })
+    } else {
+        generator
+            .generate_card_stream(options)
+            .and_then(|mut stream| stream.try_for_each(|card| write(&card?)))
+    };
+
+    if let Err(e) = result {
+        // 出错或取消时删除未写完的文件
+        drop(exporter);
+        let _ = fs::remove_file(path);
+        report(0, true, e == CANCELLED);
+        return Err(e);
+    }
+
+    let written = exporter.written();
+    exporter.finish()?;
+    report(written, true, false);
+    Ok(written)
+}
+
+/// 取消文件生成任务，任务不存在时返回 `false`
+#[tauri::command(rename_all = "snake_case")]
+pub async fn cancel_generation(
+    job_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let jobs = state.jobs.lock().map_err(|e| e.to_string())?;
+    Ok(match jobs.get(&job_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    })
+}
+
+/// 验证信用卡号码，提供安全码时同时按网络规则检查
+#[tauri::command(rename_all = "snake_case")]
+pub async fn validate_card(
+    card_number: String,
+    cvv: Option<String>,
+    locale: Option<Locale>,
+    state: State<'_, AppState>,
+) -> Result<ValidationResult, String> {
+    let generator = state.catalog.read().map_err(|e| e.to_string())?;
+    let mut result = validate_card_with_cvv(
+        &card_number,
+        cvv.as_deref(),
+        generator.registry(),
+        generator.bin_database(),
+    );
+    result.localize(locale.unwrap_or_default());
+    Ok(result)
+}
+
+/// 批量验证：卡号列表、粘贴的文本或文件（PIPE / CSV / JSON，`format` 为空时自动识别），
+/// 返回逐行结果与汇总
+#[tauri::command(rename_all = "snake_case")]
+pub async fn validate_cards(
+    card_numbers: Option<Vec<String>>,
+    text: Option<String>,
+    path: Option<String>,
+    format: Option<String>,
+    locale: Option<Locale>,
+    state: State<'_, AppState>,
+) -> Result<BatchValidation, String> {
+    let generator = state.catalog.read().map_err(|e| e.to_string())?;
+    let (registry, bin_database) = (generator.registry(), generator.bin_database());
+    let mut batch = match (card_numbers, text, path) {
+        (Some(card_numbers), _, _) => Ok(validate_card_list(&card_numbers, registry, bin_database)),
+        (None, Some(text), _) => validate_card_text(&text, format.as_deref(), registry, bin_database),
+        (None, None, Some(path)) => {
+            let text = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+            validate_card_text(&text, format.as_deref(), registry, bin_database)
+        }
+        (None, None, None) => Err("需要提供卡号列表、文本或文件路径".to_string()),
+    }?;
+    batch.localize(locale.unwrap_or_default());
+    Ok(batch)
+}
+
+/// 验证整条卡记录（卡号、有效期、安全码，可选姓名与磁道数据），逐字段返回结果；
+/// `today`（YYYY-MM-DD）为空时按今天判断是否过期
+#[tauri::command(rename_all = "snake_case")]
+pub async fn validate_card_record(
+    record: CardRecord,
+    today: Option<String>,
+    locale: Option<Locale>,
+    state: State<'_, AppState>,
+) -> Result<RecordValidation, String> {
+    let generator = state.generator(None, today.as_deref())?;
+    let mut result = validate_record(
+        &record,
+        generator.clock().today(),
+        generator.registry(),
+        generator.bin_database(),
+    );
+    result.localize(locale.unwrap_or_default());
+    Ok(result)
 }
 
-/// 验证信用卡号码
+/// 解码十六进制 EMV 数据（BER-TLV），逐个列出标签
 #[tauri::command]
-pub async fn validate_card(card_number: String) -> Result<ValidationResult, String> {
-    Ok(validate_card_number(&card_number))
+pub async fn decode_emv(data: String) -> Result<Vec<EmvTag>, String> {
+    emv::decode(&data)
+}
+
+/// 验证 EMV 数据中的卡号
+#[tauri::command]
+pub async fn validate_emv(
+    data: String,
+    locale: Option<Locale>,
+    state: State<'_, AppState>,
+) -> Result<ValidationResult, String> {
+    let generator = state.catalog.read().map_err(|e| e.to_string())?;
+    let mut result = validate_emv_data_with(&data, generator.registry(), generator.bin_database())?;
+    result.localize(locale.unwrap_or_default());
+    Ok(result)
+}
+
+/// 由测试 CVK 计算卡验证值，`expiry` 为 YYMM，磁条 CVV 需要提供服务码
+#[tauri::command(rename_all = "snake_case")]
+pub async fn compute_cvv(
+    card_number: String,
+    expiry: String,
+    kind: CvvKind,
+    service_code: Option<String>,
+    cvk: CvkPair,
+) -> Result<String, String> {
+    let service_code = kind.service_code(service_code.as_deref())?;
+    namso_core::cvv::compute_cvv(&card_number, &expiry, service_code, &cvk)
+}
+
+/// 用测试 CVK 校验卡验证值
+#[tauri::command(rename_all = "snake_case")]
+pub async fn verify_cvv(
+    card_number: String,
+    expiry: String,
+    kind: CvvKind,
+    service_code: Option<String>,
+    cvk: CvkPair,
+    cvv: String,
+) -> Result<bool, String> {
+    let service_code = kind.service_code(service_code.as_deref())?;
+    namso_core::cvv::verify_cvv(&card_number, &expiry, service_code, &cvk, &cvv)
+}
+
+/// 保格式加密卡号：保留 BIN、同网络同长度、校验位有效，同一密钥与调整值结果固定
+#[tauri::command(rename_all = "snake_case")]
+pub async fn encrypt_pans(
+    card_numbers: Vec<String>,
+    mode: FpeMode,
+    key: FpeKey,
+    tweak: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let cipher = PanCipher::with_hex_tweak(mode, &key, tweak.as_deref().unwrap_or(""))?;
+    let generator = state.catalog.read().map_err(|e| e.to_string())?;
+    card_numbers
+        .iter()
+        .map(|number| cipher.encrypt_pan(number, generator.registry()))
+        .collect()
+}
+
+/// 用同一密钥与调整值还原保格式加密过的卡号
+#[tauri::command(rename_all = "snake_case")]
+pub async fn decrypt_pans(
+    card_numbers: Vec<String>,
+    mode: FpeMode,
+    key: FpeKey,
+    tweak: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let cipher = PanCipher::with_hex_tweak(mode, &key, tweak.as_deref().unwrap_or(""))?;
+    let generator = state.catalog.read().map_err(|e| e.to_string())?;
+    card_numbers
+        .iter()
+        .map(|number| cipher.decrypt_pan(number, generator.registry()))
+        .collect()
+}
+
+/// 为卡分配网络令牌并写入令牌库文件，同一卡号与请求方已有令牌时沿用
+#[tauri::command(rename_all = "snake_case")]
+pub async fn tokenize_cards(
+    cards: Vec<CardData>,
+    options: Option<TokenOptions>,
+    seed: Option<u64>,
+    today: Option<String>,
+    vault_file: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<TokenRecord>, String> {
+    let mut generator = state.generator(seed, today.as_deref())?;
+    let options = options.unwrap_or_default();
+
+    let mut vault = TokenVault::load(&vault_file)?;
+    let records = cards
+        .iter()
+        .map(|card| generator.tokenize(card, &options, &mut vault))
+        .collect::<Result<Vec<_>, String>>()?;
+    vault.save(&vault_file)?;
+    Ok(records)
+}
+
+/// 由令牌查找资金卡
+#[tauri::command(rename_all = "snake_case")]
+pub async fn detokenize(
+    token: String,
+    vault_file: String,
+) -> Result<TokenRecord, String> {
+    TokenVault::load(&vault_file)?.detokenize(&token).cloned()
 }
 
 /// 导出为指定格式
@@ -49,15 +418,7 @@ pub async fn export_cards(
     cards: Vec<CardData>,
     format: String,
 ) -> Result<String, String> {
-    match format.to_uppercase().as_str() {
-        "PIPE" => Ok(export_to_pipe(&cards)),
-        "CSV" => export_to_csv(&cards),
-        "JSON" => export_to_json(&cards),
-        "XML" => export_to_xml(&cards),
-        "SQL" => Ok(export_to_sql(&cards, "test_cards")),
-        "CARD" => Ok(export_to_card(&cards)),
-        _ => Err(format!("不支持的导出格式: {}", format)),
-    }
+    export_cards_as(&cards, &format)
 }
 
 /// 生成虚假用户数据
@@ -103,11 +464,53 @@ pub async fn get_currencies() -> Result<Vec<(String, String)>, String> {
 
 /// 获取支持的卡网络列表
 #[tauri::command]
-pub async fn get_networks() -> Result<Vec<(String, String)>, String> {
-    Ok(CardNetworkRegistry::get_network_names()
-        .into_iter()
-        .map(|(id, name)| (id.to_string(), name.to_string()))
-        .collect())
+pub async fn get_networks(state: State<'_, AppState>) -> Result<Vec<(String, String)>, String> {
+    let generator = state.catalog.read().map_err(|e| e.to_string())?;
+    Ok(generator.registry().get_network_names())
 }
 
+/// 注册自定义卡网络（标识相同时覆盖原定义）
+#[tauri::command]
+pub async fn register_network(
+    network: CardNetwork,
+    state: State<'_, AppState>,
+) -> Result<Vec<(String, String)>, String> {
+    let mut generator = state.catalog.write().map_err(|e| e.to_string())?;
+    generator.registry_mut().register_network(network)?;
+    Ok(generator.registry().get_network_names())
+}
 
+/// 从 TOML / JSON 定义文件加载卡网络并合并到当前注册表
+#[tauri::command]
+pub async fn load_networks(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<(String, String)>, String> {
+    let networks = CardNetworkRegistry::from_file(&path)?;
+    let mut generator = state.catalog.write().map_err(|e| e.to_string())?;
+    generator.registry_mut().merge(networks)?;
+    Ok(generator.registry().get_network_names())
+}
+
+/// 查询卡号或 BIN 的发卡行信息
+#[tauri::command(rename_all = "snake_case")]
+pub async fn lookup_bin(
+    card_number: String,
+    state: State<'_, AppState>,
+) -> Result<Option<BinInfo>, String> {
+    let digits: String = card_number.chars().filter(|c| c.is_ascii_digit()).collect();
+    let generator = state.catalog.read().map_err(|e| e.to_string())?;
+    Ok(generator.bin_database().lookup(&digits).cloned())
+}
+
+/// 从 CSV / JSON 文件加载 BIN 元数据并合并到当前 BIN 表，返回合并后的条目数
+#[tauri::command]
+pub async fn load_bin_database(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let update = BinDatabase::from_file(&path)?;
+    let mut generator = state.catalog.write().map_err(|e| e.to_string())?;
+    generator.bin_database_mut().merge(update);
+    Ok(generator.bin_database().len())
+}
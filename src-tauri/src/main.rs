@@ -1,32 +1,77 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod card_generator;
 mod commands;
-mod exporters;
-mod fake_data;
-mod networks;
-mod validator;
 
-use card_generator::CreditCardGenerator;
 use commands::AppState;
-use std::sync::Mutex;
+use namso_core::bin_database::BinDatabase;
+use namso_core::card_generator::CreditCardGenerator;
+use namso_core::networks::CardNetworkRegistry;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 
 fn main() {
-    let generator = CreditCardGenerator::new();
+    // 附加数据文件无法加载时退回内置数据，启动后以对话框提示（Windows 发布版没有控制台）
+    let mut startup_errors = Vec::new();
+    // 可通过 NAMSO_NETWORKS 指定额外的网络定义文件（TOML / JSON）
+    let registry = match std::env::var("NAMSO_NETWORKS") {
+        Ok(path) => CardNetworkRegistry::builtin_with_file(&path).unwrap_or_else(|e| {
+            startup_errors.push(format!("无法加载网络定义 {}: {}，已使用内置网络", path, e));
+            CardNetworkRegistry::builtin()
+        }),
+        Err(_) => CardNetworkRegistry::builtin(),
+    };
+    // 可通过 NAMSO_BINS 指定额外的 BIN 元数据文件（CSV / JSON）
+    let bin_database = match std::env::var("NAMSO_BINS") {
+        Ok(path) => BinDatabase::builtin_with_file(&path).unwrap_or_else(|e| {
+            startup_errors.push(format!("无法加载 BIN 表 {}: {}，已使用内置 BIN 表", path, e));
+            BinDatabase::builtin()
+        }),
+        Err(_) => BinDatabase::builtin(),
+    };
+    for error in &startup_errors {
+        eprintln!("{}", error);
+    }
+    let generator = CreditCardGenerator::new()
+        .with_registry(registry)
+        .with_bin_database(bin_database);
     
     tauri::Builder::default()
-        .manage(AppState {
-            generator: Mutex::new(generator),
+        .plugin(tauri_plugin_dialog::init())
+        .setup(move |app| {
+            if !startup_errors.is_empty() {
+                app.dialog()
+                    .message(startup_errors.join("\n"))
+                    .kind(MessageDialogKind::Error)
+                    .title("数据文件加载失败")
+                    .show(|_| {});
+            }
+            Ok(())
         })
+        .manage(AppState::new(generator))
         .invoke_handler(tauri::generate_handler![
             commands::generate_cards,
+            commands::generate_cards_to_file,
+            commands::cancel_generation,
             commands::validate_card,
+            commands::validate_cards,
+            commands::validate_card_record,
+            commands::decode_emv,
+            commands::validate_emv,
+            commands::compute_cvv,
+            commands::verify_cvv,
+            commands::encrypt_pans,
+            commands::decrypt_pans,
+            commands::tokenize_cards,
+            commands::detokenize,
             commands::export_cards,
             commands::generate_users,
             commands::generate_lorem,
             commands::get_currencies,
             commands::get_networks,
+            commands::register_network,
+            commands::load_networks,
+            commands::lookup_bin,
+            commands::load_bin_database,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -4,7 +4,7 @@ import { generateCards, exportCards, getNetworks, getCurrencies } from '../lib/u
 import { useStore } from '../lib/store';
 import type { CardNetwork, ExportFormat } from '../lib/types';
 
-const EXPORT_FORMATS: ExportFormat[] = ['PIPE', 'CSV', 'JSON', 'XML', 'SQL'];
+const EXPORT_FORMATS: ExportFormat[] = ['PIPE', 'CSV', 'JSON', 'XML', 'SQL', 'TRACK', 'EMV'];
 const MONTHS = Array.from({ length: 12 }, (_, i) => (i + 1).toString().padStart(2, '0'));
 const QUANTITIES = [10, 50, 100, 500, 1000, 2000, 5000, 10000];
 
@@ -15,6 +15,7 @@ export function AdvancedMode() {
   const [year, setYear] = useState<string>('random');
   const [includeCvv, setIncludeCvv] = useState(true);
   const [includeBalance, setIncludeBalance] = useState(false);
+  const [includeTracks, setIncludeTracks] = useState(false);
   const [currency, setCurrency] = useState('USD');
   const [quantity, setQuantity] = useState(100);
   const [binCode, setBinCode] = useState('');
@@ -59,6 +60,8 @@ export function AdvancedMode() {
         include_balance: includeBalance,
         currency: includeBalance ? currency : null,
         bin_code: binCode.trim() || null,
+        // TRACK 格式需要磁道数据
+        include_tracks: includeTracks || format === 'TRACK',
       };
       
       console.log('高级模式生成参数:', params);
@@ -100,7 +103,7 @@ export function AdvancedMode() {
           className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-purple-500 focus:border-transparent"
         />
         <p className="mt-1 text-xs text-gray-500">
-          💡 提示: 可使用 x 作为占位符，如: 559888039xxxxxxx
+          💡 提示: x 为任意数字，[0-4] 为数字集合，x{6} 为重复，c 为校验位，如: 5598[0-4]8039xxxxxxx
         </p>
       </div>
       
@@ -198,6 +201,19 @@ export function AdvancedMode() {
             💰 包含余额
           </label>
         </div>
+        
+        <div className="flex items-center gap-2">
+          <input
+            type="checkbox"
+            id="adv-includeTracks"
+            checked={includeTracks}
+            onChange={(e) => setIncludeTracks(e.target.checked)}
+            className="w-4 h-4 text-purple-600 rounded focus:ring-purple-500"
+          />
+          <label htmlFor="adv-includeTracks" className="text-sm font-medium text-gray-700">
+            🧲 包含磁道数据（Track 1 / 2）
+          </label>
+        </div>
       </div>
       
       {/* 货币 */}
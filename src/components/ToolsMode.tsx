@@ -1,11 +1,24 @@
 import { useState } from 'react';
 import { Search, Users, FileText } from 'lucide-react';
-import { validateCard, generateUsers, generateLorem } from '../lib/utils';
+import { validateCard, validateCardRecord, validateCards, validateEmv, generateUsers, generateLorem } from '../lib/utils';
 import { useStore } from '../lib/store';
+import type { CardRecord, RecordField } from '../lib/types';
+
+const FIELD_LABELS: Record<RecordField, string> = {
+  number: '卡号',
+  expiry: '有效期',
+  cvv: '安全码',
+  cardholder: '持卡人',
+  track1: 'Track 1',
+  track2: 'Track 2',
+};
 
 export function ToolsMode() {
   const [cardNumber, setCardNumber] = useState('');
+  const [cvv, setCvv] = useState('');
   const [validationResult, setValidationResult] = useState<string>('');
+  const [batchText, setBatchText] = useState('');
+  const [record, setRecord] = useState<CardRecord>({ number: '', expiry: '', cvv: '', cardholder: '', track1: '', track2: '' });
   const [loremCount, setLoremCount] = useState(3);
   
   const { setResult, setLoading, setError } = useStore();
@@ -17,17 +30,104 @@ export function ToolsMode() {
     }
     
     try {
-      const result = await validateCard(cardNumber);
+      // 超过 19 位或含 A-F 的输入按十六进制 EMV 数据（BER-TLV）处理
+      const cleaned = cardNumber.replace(/[\s-]/g, '');
+      const isEmv = cleaned.length > 19 || /[a-f]/i.test(cleaned);
+      const result = isEmv
+        ? await validateEmv(cardNumber)
+        : await validateCard(cardNumber, cvv.trim() || null);
       if (result.valid) {
-        setValidationResult(`✅ 有效 - ${result.network} (${result.length}位)`);
+        let message = `✅ 有效 - ${result.network} (${result.length}位)`;
+        if (result.bin_info) {
+          const info = result.bin_info;
+          message += `\n🏦 ${info.issuer} · ${info.country} · ${info.card_type} · ${info.tier}`;
+        }
+        if (result.ambiguous) {
+          const candidates = result.candidates.map((c) => `${c.name} (${c.range})`).join(' / ');
+          message += `\n🔀 联名/重叠区间: ${candidates}`;
+        }
+        if (result.cvv_valid) {
+          message += `\n🔒 安全码有效`;
+        }
+        if (result.cleaned) {
+          message += `\n🧹 已去除空格与分隔符`;
+        }
+        setValidationResult(message);
       } else {
-        setValidationResult(`❌ 无效 - ${result.reason}`);
+        let message = `❌ 无效 - ${result.reason}`;
+        if (result.network && result.allowed_lengths.length > 0) {
+          message += `\n💳 ${result.network} 允许的长度: ${result.allowed_lengths.join(' / ')} 位`;
+        }
+        setValidationResult(message);
       }
     } catch (error) {
       setValidationResult('❌ 验证失败');
     }
   };
   
+  const handleValidateBatch = async () => {
+    if (!batchText.trim()) {
+      return;
+    }
+    
+    try {
+      setLoading(true);
+      setError(null);
+      
+      const { format, results, summary } = await validateCards(batchText);
+      
+      let result = `🔍 批量验证 (${format})\n` + '='.repeat(50) + '\n\n';
+      result += `共 ${summary.total} 条: ✅ 有效 ${summary.valid} · ❌ 无效 ${summary.invalid}\n\n`;
+      Object.entries(summary.by_network).forEach(([network, count]) => {
+        result += `💳 ${summary.network_labels[network] ?? network}: 有效 ${count.valid} · 无效 ${count.invalid}\n`;
+      });
+      Object.entries(summary.by_reason).forEach(([reason, count]) => {
+        result += `⚠️ ${reason}: ${count}\n`;
+      });
+      result += '\n' + '─'.repeat(40) + '\n';
+      results.forEach(({ line, input, result: r }) => {
+        result += `${r.valid ? '✅' : '❌'} 第 ${line} 行 ${input} - ${r.network ?? '未知'} · ${r.reason}\n`;
+      });
+      
+      setResult(result);
+    } catch (error) {
+      setError(error instanceof Error ? error.message : '验证失败');
+    } finally {
+      setLoading(false);
+    }
+  };
+  
+  const handleValidateRecord = async () => {
+    if (!record.number.trim()) {
+      return;
+    }
+    
+    try {
+      setLoading(true);
+      setError(null);
+      
+      // 未填写的可选字段不参与验证
+      const { valid, card, fields } = await validateCardRecord({
+        ...record,
+        cardholder: record.cardholder?.trim() || null,
+        track1: record.track1?.trim() || null,
+        track2: record.track2?.trim() || null,
+      });
+      
+      let result = '🧾 整卡记录验证\n' + '='.repeat(50) + '\n\n';
+      result += `${valid ? '✅ 全部有效' : '❌ 存在无效字段'} - ${card.network ?? '未知网络'}\n\n`;
+      fields.forEach(({ field, valid: fieldValid, reason }) => {
+        result += `${fieldValid ? '✅' : '❌'} ${FIELD_LABELS[field]}: ${reason}\n`;
+      });
+      
+      setResult(result);
+    } catch (error) {
+      setError(error instanceof Error ? error.message : '验证失败');
+    } finally {
+      setLoading(false);
+    }
+  };
+  
   const handleGenerateUsers = async () => {
     try {
       setLoading(true);
@@ -93,6 +193,15 @@ export function ToolsMode() {
               className="flex-1 px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-green-500 focus:border-transparent font-mono"
               onKeyPress={(e) => e.key === 'Enter' && handleValidate()}
             />
+            <input
+              type="text"
+              value={cvv}
+              onChange={(e) => setCvv(e.target.value)}
+              placeholder="CVV（可选）"
+              maxLength={4}
+              className="w-32 px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-green-500 focus:border-transparent font-mono"
+              onKeyPress={(e) => e.key === 'Enter' && handleValidate()}
+            />
             <button
               onClick={handleValidate}
               className="bg-green-600 hover:bg-green-700 text-white font-medium py-2 px-6 rounded-lg transition-colors duration-200"
@@ -109,12 +218,99 @@ export function ToolsMode() {
                 ? 'bg-yellow-50 text-yellow-800'
                 : 'bg-red-50 text-red-800'
             }`}>
-              <p className="font-medium">{validationResult}</p>
+              <p className="font-medium whitespace-pre-line">{validationResult}</p>
             </div>
           )}
         </div>
       </div>
       
+      {/* 批量验证 */}
+      <div className="bg-white rounded-lg border border-gray-200 p-6">
+        <h3 className="text-lg font-semibold text-gray-800 mb-4 flex items-center gap-2">
+          <Search className="w-5 h-5" />
+          📋 批量验证
+        </h3>
+        
+        <div className="space-y-3">
+          <textarea
+            value={batchText}
+            onChange={(e) => setBatchText(e.target.value)}
+            placeholder="粘贴卡号列表或 PIPE / CSV / JSON 导出内容"
+            rows={6}
+            className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-green-500 focus:border-transparent font-mono text-sm"
+          />
+          <button
+            onClick={handleValidateBatch}
+            className="w-full bg-green-600 hover:bg-green-700 text-white font-medium py-2 px-4 rounded-lg transition-colors duration-200"
+          >
+            批量验证
+          </button>
+        </div>
+      </div>
+      
+      {/* 整卡记录验证 */}
+      <div className="bg-white rounded-lg border border-gray-200 p-6">
+        <h3 className="text-lg font-semibold text-gray-800 mb-4 flex items-center gap-2">
+          <Search className="w-5 h-5" />
+          🧾 整卡记录验证
+        </h3>
+        
+        <div className="space-y-3">
+          <div className="flex gap-3">
+            <input
+              type="text"
+              value={record.number}
+              onChange={(e) => setRecord({ ...record, number: e.target.value })}
+              placeholder="卡号"
+              className="flex-1 px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-green-500 focus:border-transparent font-mono"
+            />
+            <input
+              type="text"
+              value={record.expiry}
+              onChange={(e) => setRecord({ ...record, expiry: e.target.value })}
+              placeholder="MM/YY"
+              maxLength={7}
+              className="w-28 px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-green-500 focus:border-transparent font-mono"
+            />
+            <input
+              type="text"
+              value={record.cvv}
+              onChange={(e) => setRecord({ ...record, cvv: e.target.value })}
+              placeholder="CVV"
+              maxLength={4}
+              className="w-24 px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-green-500 focus:border-transparent font-mono"
+            />
+          </div>
+          <input
+            type="text"
+            value={record.cardholder ?? ''}
+            onChange={(e) => setRecord({ ...record, cardholder: e.target.value })}
+            placeholder="持卡人姓名（可选）"
+            className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-green-500 focus:border-transparent"
+          />
+          <input
+            type="text"
+            value={record.track1 ?? ''}
+            onChange={(e) => setRecord({ ...record, track1: e.target.value })}
+            placeholder="Track 1（可选）"
+            className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-green-500 focus:border-transparent font-mono text-sm"
+          />
+          <input
+            type="text"
+            value={record.track2 ?? ''}
+            onChange={(e) => setRecord({ ...record, track2: e.target.value })}
+            placeholder="Track 2（可选）"
+            className="w-full px-4 py-2 border border-gray-300 rounded-lg focus:ring-2 focus:ring-green-500 focus:border-transparent font-mono text-sm"
+          />
+          <button
+            onClick={handleValidateRecord}
+            className="w-full bg-green-600 hover:bg-green-700 text-white font-medium py-2 px-4 rounded-lg transition-colors duration-200"
+          >
+            验证记录
+          </button>
+        </div>
+      </div>
+      
       {/* 虚假用户数据生成 */}
       <div className="bg-white rounded-lg border border-gray-200 p-6">
         <h3 className="text-lg font-semibold text-gray-800 mb-4 flex items-center gap-2">
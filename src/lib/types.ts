@@ -1,5 +1,26 @@
 // TypeScript 类型定义
 
+export type CardType = 'credit' | 'debit' | 'prepaid';
+
+export type CardSegment = 'consumer' | 'commercial';
+
+export interface BinCriteria {
+  country?: string | null;
+  card_type?: CardType | null;
+  segment?: CardSegment | null;
+  tier?: string | null;
+}
+
+export interface BinInfo {
+  bin: string;
+  network: string;
+  issuer: string;
+  country: string;
+  card_type: CardType;
+  tier: string;
+  segment: CardSegment;
+}
+
 export interface CardData {
   number: string;
   network: string;
@@ -7,17 +28,118 @@ export interface CardData {
   exp_year: string;
   expiry: string;
   cvv?: string;
+  cvv_label?: string;
   balance?: number;
   currency?: string;
   bin: string;
+  bin_info?: BinInfo;
+  cardholder?: string;
+  service_code?: string;
+  track1?: string;
+  track2?: string;
+}
+
+export interface NetworkCandidate {
+  identifier: string;
+  name: string;
+  range: string;
 }
 
+// 验证失败原因，code 为稳定的错误码，提示文字见 ValidationResult.reason
+export type ValidationError =
+  | { code: 'non_digit_characters' }
+  | { code: 'invalid_length'; length: number }
+  | { code: 'luhn_failed' }
+  | { code: 'check_digit_failed'; algorithm: string }
+  | { code: 'unknown_network' }
+  | { code: 'length_not_allowed_for_network'; network: string; length: number; allowed: number[] }
+  | { code: 'no_security_code'; network: string }
+  | { code: 'invalid_security_code'; label: string; length: number }
+  | { code: 'emv_pan_mismatch' }
+  | { code: 'invalid_expiry' }
+  | { code: 'expired'; expiry: string }
+  | { code: 'invalid_cardholder_name' }
+  | { code: 'malformed_track'; track: number }
+  | { code: 'track_lrc_invalid'; track: number }
+  | { code: 'track_pan_mismatch'; track: number }
+  | { code: 'track_expiry_mismatch'; track: number }
+  | { code: 'track_name_mismatch' };
+
+export type Locale = 'zh' | 'en';
+
 export interface ValidationResult {
   valid: boolean;
   luhn_valid: boolean;
+  // 长度不符合时为按前缀最匹配的网络
   network: string | null;
+  allowed_lengths: number[];
+  candidates: NetworkCandidate[];
+  ambiguous: boolean;
   length: number;
+  // 输入中是否有被清理掉的字符（空格、-、字母等）
+  cleaned: boolean;
   reason: string;
+  error: ValidationError | null;
+  bin_info: BinInfo | null;
+  cvv_valid: boolean | null;
+}
+
+export interface LineValidation {
+  line: number;
+  input: string;
+  result: ValidationResult;
+}
+
+export interface ValidationSummary {
+  total: number;
+  valid: number;
+  invalid: number;
+  by_reason: Record<string, number>;
+  by_code: Record<string, number>;
+  // 无法识别网络的卡号记在 `unknown` 下
+  by_network: Record<string, { valid: number; invalid: number }>;
+  // by_network 各键的展示文字（随 locale 变化）
+  network_labels: Record<string, string>;
+}
+
+export interface BatchValidation {
+  format: 'PIPE' | 'CSV' | 'JSON';
+  results: LineValidation[];
+  summary: ValidationSummary;
+}
+
+// 待验证的整条卡记录，expiry 为 MM/YY、MM/YYYY 或 MMYY
+export interface CardRecord {
+  number: string;
+  expiry: string;
+  cvv: string;
+  cardholder?: string | null;
+  track1?: string | null;
+  track2?: string | null;
+}
+
+export type RecordField = 'number' | 'expiry' | 'cvv' | 'cardholder' | 'track1' | 'track2';
+
+export interface FieldValidation {
+  field: RecordField;
+  valid: boolean;
+  reason: string;
+  error: ValidationError | null;
+}
+
+export interface RecordValidation {
+  valid: boolean;
+  card: ValidationResult;
+  // 未提供的可选字段（姓名、磁道）不出现
+  fields: FieldValidation[];
+}
+
+export interface EmvTag {
+  tag: string;
+  name: string | null;
+  value: string;
+  text: string | null;
+  children?: EmvTag[];
 }
 
 export interface FakeUser {
@@ -27,18 +149,92 @@ export interface FakeUser {
   address: string;
 }
 
-export type ExportFormat = 'CARD' | 'PIPE' | 'CSV' | 'JSON' | 'XML' | 'SQL';
+export interface NetworkDefinition {
+  name: string;
+  identifier: string;
+  ranges: string[];
+  length: number[];
+  cvv_length: number;
+  cvv_label?: string;
+  // 内置算法名称，或注册表中注册的自定义算法名称；mod97_10 为 ISO 7064 Mod 97-10，末尾两位为校验位
+  check_digit?: 'luhn' | 'none' | 'verhoeff' | 'damm' | 'mod97_10' | (string & {});
+}
+
+export type ExportFormat = 'CARD' | 'PIPE' | 'CSV' | 'JSON' | 'XML' | 'SQL' | 'TRACK' | 'EMV';
 export type CardNetwork = 'random' | 'visa' | 'mastercard' | 'amex' | 'discover' | 'unionpay' | 'diners';
 
+export type ExpiryPolicy =
+  | { type: 'future_range'; min_months: number; max_months: number }
+  | { type: 'exact'; month: number; year: number }
+  | { type: 'expired_by'; months: number }
+  | { type: 'edge_of_month' };
+
 export interface GenerateCardsParams {
   network: CardNetwork;
   quantity: number;
+  expiry?: ExpiryPolicy | null;
   exp_month: number | null;
   exp_year: number | null;
   include_cvv: boolean;
+  cvv_length?: number | null;
+  cvk?: string | null;
   include_balance: boolean;
   currency: string | null;
   bin_code: string | null;
+  criteria?: BinCriteria | null;
+  // 随机种子，须与 today 一起指定，相同种子与日期得到完全相同的结果
+  seed?: number | null;
+  // YYYY-MM-DD，以该日期代替今天计算过期日期；指定 seed 时必填
+  today?: string | null;
+  unique?: boolean | null;
+  history_file?: string | null;
+  parallel?: boolean | null;
+  include_tracks?: boolean | null;
+  service_code?: string | null;
+}
+
+export type CvvKind = 'cvv' | 'icvv' | 'cvv2';
+
+export interface CvvParams {
+  card_number: string;
+  expiry: string;
+  kind: CvvKind;
+  service_code?: string | null;
+  cvk: string;
+}
+
+export type FpeMode = 'ff1' | 'ff3_1';
+
+export interface FpeParams {
+  mode: FpeMode;
+  key: string;
+  tweak?: string | null;
+}
+
+export interface TokenOptions {
+  requestor_id?: string | null;
+  token_bin?: string | null;
+  expiry?: ExpiryPolicy | null;
+}
+
+export interface TokenRecord {
+  token: string;
+  token_exp_month: string;
+  token_exp_year: string;
+  token_expiry: string;
+  requestor_id: string;
+  pan: string;
+  pan_expiry: string;
+  network: string;
+  created: string;
+}
+
+export interface GenerationProgress {
+  job_id: string;
+  generated: number;
+  total: number;
+  done: boolean;
+  cancelled: boolean;
 }
 
 export interface AppStore {
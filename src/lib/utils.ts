@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { CardData, ExportFormat, GenerateCardsParams, ValidationResult, FakeUser } from './types';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { BatchValidation, CardData, CardRecord, CvvParams, EmvTag, ExportFormat, GenerateCardsParams, GenerationProgress, ValidationResult, FakeUser, NetworkDefinition, BinInfo, FpeParams, Locale, RecordValidation, TokenOptions, TokenRecord } from './types';
 
 // Tauri 命令调用
 
@@ -7,17 +8,129 @@ export async function generateCards(params: GenerateCardsParams): Promise<CardDa
   return await invoke('generate_cards', {
     network: params.network,
     quantity: params.quantity,
+    expiry: params.expiry ?? null,
     exp_month: params.exp_month ?? null,
     exp_year: params.exp_year ?? null,
     include_cvv: params.include_cvv,
+    cvv_length: params.cvv_length ?? null,
+    cvk: params.cvk ?? null,
     include_balance: params.include_balance,
     currency: params.currency ?? null,
     bin_code: params.bin_code ?? null,
+    criteria: params.criteria ?? null,
+    seed: params.seed ?? null,
+    today: params.today ?? null,
+    unique: params.unique ?? null,
+    history_file: params.history_file ?? null,
+    parallel: params.parallel ?? null,
+    include_tracks: params.include_tracks ?? null,
+    service_code: params.service_code ?? null,
   });
 }
 
-export async function validateCard(card_number: string): Promise<ValidationResult> {
-  return await invoke('validate_card', { card_number });
+// 大批量生成直接写入文件，返回写入的数量；进度通过 onGenerationProgress 获取
+export async function generateCardsToFile(
+  job_id: string,
+  params: GenerateCardsParams,
+  path: string,
+  format: ExportFormat,
+): Promise<number> {
+  return await invoke('generate_cards_to_file', {
+    job_id,
+    options: {
+      network: params.network,
+      quantity: params.quantity,
+      expiry: params.expiry ?? null,
+      exp_month: params.exp_month ?? null,
+      exp_year: params.exp_year ?? null,
+      include_cvv: params.include_cvv,
+      cvv_length: params.cvv_length ?? null,
+      cvk: params.cvk ?? null,
+      include_balance: params.include_balance,
+      currency: params.currency ?? null,
+      bin_code: params.bin_code ?? null,
+      criteria: params.criteria ?? {},
+      unique: params.unique ?? false,
+      include_tracks: params.include_tracks ?? false,
+      service_code: params.service_code ?? null,
+    },
+    seed: params.seed ?? null,
+    today: params.today ?? null,
+    parallel: params.parallel ?? null,
+    path,
+    format,
+  });
+}
+
+export async function cancelGeneration(job_id: string): Promise<boolean> {
+  return await invoke('cancel_generation', { job_id });
+}
+
+export async function onGenerationProgress(handler: (progress: GenerationProgress) => void): Promise<UnlistenFn> {
+  return await listen<GenerationProgress>('generation-progress', (event) => handler(event.payload));
+}
+
+export async function validateCard(card_number: string, cvv?: string | null, locale?: Locale): Promise<ValidationResult> {
+  return await invoke('validate_card', { card_number, cvv: cvv ?? null, locale: locale ?? null });
+}
+
+// 批量验证粘贴的文本（PIPE / CSV / JSON，format 为空时自动识别）
+export async function validateCards(text: string, format?: 'PIPE' | 'CSV' | 'JSON', locale?: Locale): Promise<BatchValidation> {
+  return await invoke('validate_cards', { text, format: format ?? null, locale: locale ?? null });
+}
+
+// 逐字段验证整条卡记录，today（YYYY-MM-DD）为空时按今天判断是否过期
+export async function validateCardRecord(record: CardRecord, today?: string | null, locale?: Locale): Promise<RecordValidation> {
+  return await invoke('validate_card_record', { record, today: today ?? null, locale: locale ?? null });
+}
+
+// 解码 / 验证十六进制 EMV 数据（BER-TLV）
+export async function decodeEmv(data: string): Promise<EmvTag[]> {
+  return await invoke('decode_emv', { data });
+}
+
+export async function validateEmv(data: string, locale?: Locale): Promise<ValidationResult> {
+  return await invoke('validate_emv', { data, locale: locale ?? null });
+}
+
+// 由测试 CVK 计算 / 校验卡验证值，expiry 为 YYMM
+export async function computeCvv(params: CvvParams): Promise<string> {
+  return await invoke('compute_cvv', { ...params, service_code: params.service_code ?? null });
+}
+
+export async function verifyCvv(params: CvvParams, cvv: string): Promise<boolean> {
+  return await invoke('verify_cvv', { ...params, service_code: params.service_code ?? null, cvv });
+}
+
+// 保格式加密（FF1 / FF3-1）：保留 BIN，用同一密钥与调整值可还原
+export async function encryptPans(card_numbers: string[], params: FpeParams): Promise<string[]> {
+  return await invoke('encrypt_pans', { card_numbers, ...params, tweak: params.tweak ?? null });
+}
+
+export async function decryptPans(card_numbers: string[], params: FpeParams): Promise<string[]> {
+  return await invoke('decrypt_pans', { card_numbers, ...params, tweak: params.tweak ?? null });
+}
+
+// 网络令牌：令牌库保存在 vault_file（JSON）中，跨会话保持映射
+export async function tokenizeCards(
+  cards: CardData[],
+  vaultFile: string,
+  options?: TokenOptions,
+  seed?: number | null,
+  // YYYY-MM-DD，指定 seed 时必填
+  today?: string | null,
+): Promise<TokenRecord[]> {
+  return await invoke('tokenize_cards', {
+    cards,
+    options: options ?? null,
+    seed: seed ?? null,
+    today: today ?? null,
+    vault_file: vaultFile,
+  });
+}
+
+export async function detokenize(token: string, vaultFile: string): Promise<TokenRecord> {
+  return await invoke('detokenize', { token, vault_file: vaultFile });
 }
 
 export async function exportCards(cards: CardData[], format: ExportFormat): Promise<string> {
@@ -40,6 +153,22 @@ export async function getNetworks(): Promise<[string, string][]> {
   return await invoke('get_networks');
 }
 
+export async function registerNetwork(network: NetworkDefinition): Promise<[string, string][]> {
+  return await invoke('register_network', { network });
+}
+
+export async function loadNetworks(path: string): Promise<[string, string][]> {
+  return await invoke('load_networks', { path });
+}
+
+export async function lookupBin(card_number: string): Promise<BinInfo | null> {
+  return await invoke('lookup_bin', { card_number });
+}
+
+export async function loadBinDatabase(path: string): Promise<number> {
+  return await invoke('load_bin_database', { path });
+}
+
 // 工具函数
 
 export async function copyToClipboard(text: string): Promise<void> {
@@ -75,6 +204,8 @@ export async function saveToFile(content: string, format: ExportFormat): Promise
       'JSON': 'json',
       'XML': 'xml',
       'SQL': 'sql',
+      'TRACK': 'txt',
+      'EMV': 'txt',
     };
     
     const ext = extensions[format];
@@ -105,7 +236,7 @@ export function formatCardDisplay(cards: CardData[]): string {
     display += `📅 过期: ${card.expiry}\n`;
     
     if (card.cvv) {
-      display += `🔒 CVV: ${card.cvv}\n`;
+      display += `🔒 ${card.cvv_label || 'CVV'}: ${card.cvv}\n`;
     }
     
     if (card.balance) {